@@ -0,0 +1,158 @@
+use {
+    syn::{LitStr, LitInt, Ident, Token, bracketed},
+    syn::parse::{Parse, ParseStream, Result},
+    syn::punctuated::Punctuated,
+    proc_macro2::Span
+};
+
+const METHODS: [&str; 9] = ["GET", "HEAD", "POST", "PUT", "PATCH", "DELETE", "OPTIONS", "CONNECT", "TRACE"];
+
+// cors(origins = ["https://example.com"] [, methods = [GET, POST]] [, headers = ["X-Api-Key"]]
+// [, expose = ["ratelimit-remaining"]] [, max_age = 3600] [, credentials]), "*" allowing any origin
+pub struct Cors {
+    origins: Vec<String>,
+    methods: Option<Vec<String>>,
+    headers: Option<Vec<String>>,
+    expose: Vec<String>,
+    credentials: bool,
+    max_age: Option<LitInt>
+}
+
+impl Parse for Cors {
+    fn parse(stream: ParseStream) -> Result<Self> {
+        let mut origins: Option<Vec<String>> = None;
+        let mut methods: Option<Vec<String>> = None;
+        let mut headers: Option<Vec<String>> = None;
+        let mut expose: Vec<String> = Vec::new();
+        let mut credentials = false;
+        let mut max_age: Option<LitInt> = None;
+
+        while !stream.is_empty() {
+            let key: Ident = stream.parse()?;
+
+            if key == "credentials" {
+                credentials = true;
+            } else {
+                let _eq: Token![=] = stream.parse()?;
+
+                if key == "origins" {
+                    origins = Some( parse_origins(stream)? );
+                } else if key == "methods" {
+                    methods = Some( parse_methods(stream)? );
+                } else if key == "headers" {
+                    headers = Some( parse_strings(stream)?.iter().map(LitStr::value).collect() );
+                } else if key == "expose" {
+                    expose = parse_strings(stream)?.iter().map(LitStr::value).collect();
+                } else if key == "max_age" {
+                    let seconds: LitInt = stream.parse()?;
+                    seconds.base10_parse::<u32>()?;
+                    max_age = Some(seconds);
+                } else {
+                    return Err( syn::Error::new(key.span(), format!("Unknown cors option `{}`, expected one of: origins, methods, headers, expose, max_age, credentials", key)) )
+                }
+            }
+
+            if !stream.is_empty() {
+                let _comma: Token![,] = stream.parse()?;
+            }
+        }
+
+        let origins = match origins {
+            Some(origins) => origins,
+            None => return Err( syn::Error::new(Span::call_site(), "cors(..) needs the allowed origins, e.g. origins = [\"https://example.com\"] or origins = [\"*\"]") )
+        };
+
+        if credentials && origins.iter().any(|origin| origin == "*") {
+            return Err( syn::Error::new(Span::call_site(), "cors(..) cannot allow credentials from any origin, list the origins instead") )
+        }
+
+        Ok( Cors { origins, methods, headers, expose, credentials, max_age } )
+    }
+}
+
+impl Cors {
+    pub fn middleware(&self) -> proc_macro2::TokenStream {
+        let origins = &self.origins;
+        let mut tokens = quote!{ roolz::route::cors::Cors::new( &[ #( #origins ),* ] ) };
+
+        if let Some(methods) = &self.methods {
+            tokens = quote!{ #tokens.methods( &[ #( #methods ),* ] ) };
+        }
+
+        if let Some(headers) = &self.headers {
+            tokens = quote!{ #tokens.headers( &[ #( #headers ),* ] ) };
+        }
+
+        if !self.expose.is_empty() {
+            let expose = &self.expose;
+            tokens = quote!{ #tokens.expose( &[ #( #expose ),* ] ) };
+        }
+
+        if let Some(max_age) = &self.max_age {
+            tokens = quote!{ #tokens.max_age( #max_age ) };
+        }
+
+        if self.credentials {
+            tokens = quote!{ #tokens.credentials() };
+        }
+
+        tokens
+    }
+}
+
+fn parse_strings(stream: ParseStream) -> Result<Punctuated<LitStr, Token![,]>> {
+    let list;
+    bracketed!( list in stream );
+    list.parse_terminated(|item| item.parse::<LitStr>())
+}
+
+// origins are compared as sent by browsers: scheme, host and optional port, without a path
+fn parse_origins(stream: ParseStream) -> Result<Vec<String>> {
+    let mut origins: Vec<String> = Vec::new();
+
+    for origin in parse_strings(stream)? {
+        let value = origin.value();
+        let valid = value == "*" || (
+            (value.starts_with("http://") || value.starts_with("https://")) &&
+            !value.split_once("://").map(|(_, rest)| rest).unwrap_or_default().contains('/')
+        );
+
+        if !valid {
+            return Err( syn::Error::new(origin.span(), format!("Invalid origin `{}`, expected e.g. \"https://example.com\" or \"*\"", value)) )
+        }
+
+        origins.push(value);
+    }
+
+    Ok(origins)
+}
+
+// methods = [GET, POST] or methods = ["GET", "POST"]
+fn parse_methods(stream: ParseStream) -> Result<Vec<String>> {
+    let list;
+    bracketed!( list in stream );
+    let mut methods: Vec<String> = Vec::new();
+
+    while !list.is_empty() {
+        let (method, span) = if list.peek(LitStr) {
+            let method: LitStr = list.parse()?;
+            (method.value(), method.span())
+        } else {
+            let method: Ident = list.parse()?;
+            (method.to_string(), method.span())
+        };
+
+        let method = method.to_uppercase();
+        if !METHODS.contains(&method.as_str()) {
+            return Err( syn::Error::new(span, format!("Unknown method `{}`", method)) )
+        }
+
+        methods.push(method);
+
+        if !list.is_empty() {
+            let _comma: Token![,] = list.parse()?;
+        }
+    }
+
+    Ok(methods)
+}
@@ -0,0 +1,74 @@
+use {
+    syn::{LitStr, LitInt, Ident, Token},
+    syn::parse::{Parse, ParseStream, Result}
+};
+
+// files("/assets", "./public" [, max_age = 86400])
+pub struct StaticFiles {
+    path: LitStr,
+    dir: LitStr,
+    max_age: Option<LitInt>
+}
+
+impl Parse for StaticFiles {
+    fn parse(stream: ParseStream) -> Result<Self> {
+        let path: LitStr = stream.parse()?;
+        let _comma: Token![,] = stream.parse()?;
+        let dir: LitStr = stream.parse()?;
+        let mut max_age: Option<LitInt> = None;
+
+        if stream.peek(Token![,]) {
+            let _comma: Token![,] = stream.parse()?;
+            let key: Ident = stream.parse()?;
+            if key != "max_age" {
+                return Err( syn::Error::new(key.span(), format!("Unknown files option `{}`, expected `max_age`", key)) )
+            }
+            let _eq: Token![=] = stream.parse()?;
+            max_age = Some(stream.parse()?);
+        }
+
+        Ok( StaticFiles { path, dir, max_age } )
+    }
+}
+
+impl StaticFiles {
+    pub fn service(&self) -> proc_macro2::TokenStream {
+        let path = &self.path;
+        let dir = &self.dir;
+        let max_age = match &self.max_age {
+            Some(max_age) => quote!{ #max_age },
+            None => quote!{ 3600 }
+        };
+
+        quote!{ .service( roolz::route::files::files( #path, #dir, #max_age ) ) }
+    }
+}
+
+// spa("/", "./dist/index.html"), only allowed at the root of the route tree
+pub struct Spa {
+    path: LitStr,
+    index: LitStr
+}
+
+impl Parse for Spa {
+    fn parse(stream: ParseStream) -> Result<Self> {
+        let path: LitStr = stream.parse()?;
+        let _comma: Token![,] = stream.parse()?;
+        let index: LitStr = stream.parse()?;
+
+        Ok( Spa { path, index } )
+    }
+}
+
+impl Spa {
+    // registered after every other service, with the fallback as the app's default service
+    pub fn services(&self, api_prefixes: &[String]) -> proc_macro2::TokenStream {
+        let path = &self.path;
+        let index = &self.index;
+
+        quote!{
+            .service( roolz::route::files::spa( #path, #index, &[ #( #api_prefixes ),* ], prefix, ALLOWED_METHODS ) )
+            .default_service( roolz::route::files::spa_fallback( #path, #index, &[ #( #api_prefixes ),* ], prefix, ALLOWED_METHODS ) )
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use {
+    syn::{parenthesized, LitStr, Ident, Token},
+    syn::parse::{Parse, ParseStream, Result},
+    syn::punctuated::Punctuated
+};
+
+const GUARDS: [&str; 3] = ["host", "header", "content_type"];
+
+// a single entry of a guard(..) clause, e.g. host = "api.example.com" or header("X-Signature")
+pub struct Guard {
+    pub tokens: proc_macro2::TokenStream,
+    // identifies the guard when comparing routes for duplicates
+    pub key: String
+}
+
+impl Parse for Guard {
+    fn parse(stream: ParseStream) -> Result<Self> {
+        let name: Ident = stream.parse()?;
+        let args: Vec<LitStr> = if stream.peek(Token![=]) {
+            let _eq: Token![=] = stream.parse()?;
+            vec![ stream.parse()? ]
+        } else {
+            let args_stream;
+            parenthesized!( args_stream in stream );
+            let args: Punctuated<LitStr, Token![,]> = args_stream.parse_terminated(|s| s.parse::<LitStr>())?;
+            args.into_iter().collect()
+        };
+
+        let tokens = match (name.to_string().as_str(), args.as_slice()) {
+            ("host", [host]) => quote!{ actix_web::guard::Host( #host ) },
+            ("header", [header]) => quote!{ roolz::route::guards::header_present( #header ) },
+            ("header", [header, value]) => quote!{ actix_web::guard::Header( #header, #value ) },
+            ("content_type", [mime]) => quote!{ roolz::route::guards::content_type( #mime ) },
+            (guard, _) if GUARDS.contains(&guard) => {
+                return Err( syn::Error::new(name.span(), format!("Wrong number of arguments for guard `{}`", guard)) )
+            },
+            (guard, _) => {
+                return Err( syn::Error::new(name.span(), format!("Unknown guard `{}`, expected one of: {}", guard, GUARDS.join(", "))) )
+            }
+        };
+
+        let key = format!(
+            "{}({})",
+            name,
+            args.iter().map(|a| a.value()).collect::<Vec<String>>().join(",")
+        );
+
+        Ok( Guard { tokens, key } )
+    }
+}
+
+pub fn parse_guards(stream: ParseStream) -> Result<Vec<Guard>> {
+    let guards: Punctuated<Guard, Token![,]> = stream.parse_terminated(Guard::parse)?;
+    Ok( guards.into_iter().collect() )
+}
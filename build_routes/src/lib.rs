@@ -2,68 +2,298 @@
 extern crate proc_macro;
 extern crate syn;
 
+mod path;
+mod guard;
+mod openapi;
+mod files;
+mod redirect;
+mod version;
+mod mount;
+mod rate_limit;
+mod timeout;
+mod cors;
+
 use proc_macro_error::{proc_macro_error, emit_error};
 
+use guard::Guard;
+use openapi::{OpenApi, ApiDoc};
+use files::{StaticFiles, Spa};
+use redirect::Redirect;
+use version::Versions;
+use mount::Mount;
+use rate_limit::RateLimit;
+use timeout::Timeout;
+use cors::Cors;
+
 use {
-    syn::{parse_macro_input, parenthesized, braced, Path, LitStr, Ident, token},
+    syn::{parse_macro_input, parenthesized, braced, Path, LitStr, Ident, Expr, Token, token},
     syn::parse::{Parse, ParseStream, Result},
+    syn::punctuated::Punctuated,
     proc_macro::TokenStream,
     proc_macro2
 };
 
+const RESOURCE_ACTIONS: [&str; 5] = ["index", "show", "create", "update", "delete"];
+
 #[proc_macro]
 #[proc_macro_error]
 pub fn build_routes(input: TokenStream) -> TokenStream {
-    let root: RouteTree = parse_macro_input!(input as RouteTree);
-    let method_chain: proc_macro2::TokenStream = build_method_chain(root.scopes);
+    let table: RouteTable = parse_macro_input!(input as RouteTable);
+    let root: &RouteTree = &table.tree;
+    let paths_module: proc_macro2::TokenStream = build_paths_module(&root.scopes);
+    let route_table: proc_macro2::TokenStream = build_route_table(&root.scopes);
+    let shadow_warnings: proc_macro2::TokenStream = check_route_conflicts(&root.scopes);
+    let operations: proc_macro2::TokenStream = build_operations(root);
+    let openapi_document: proc_macro2::TokenStream = build_openapi_document(root);
+    let route_info: proc_macro2::TokenStream = build_route_info(root);
+    let allowed_methods: proc_macro2::TokenStream = build_allowed_methods(&root.scopes);
+    let mut method_chain: proc_macro2::TokenStream = quote!{
+        .app_data( roolz::route::extractors::json_config() )
+        .app_data( roolz::route::extractors::path_config() )
+    };
+    // files and the document sit at fixed paths, ahead of the scopes that may share their prefix
+    for files in &root.files {
+        method_chain.extend( files.service() );
+    }
+
+    if let Some(path) = root.openapi.as_ref().and_then(|openapi| openapi.path.as_ref()) {
+        method_chain.extend(
+            quote!{ .route( #path, actix_web::web::get().to( || async { roolz::view::from( openapi() ) } ) ) }
+        );
+    }
+
+    method_chain.extend( build_method_chain(&root.scopes, root.fallback.as_ref()) );
+
+    for mount in &root.mounts {
+        method_chain.extend( build_mount_call(mount, "", &Negotiation::default()) );
+    }
+
+    // the single page app claims everything under its path, so it goes last
+    if let Some(spa) = &root.spa {
+        method_chain.extend( spa.services(&api_prefixes(root)) );
+    }
+
+    match (&root.fallback, &root.spa) {
+        (Some(action), Some(_)) => emit_error!(action.segments[0].ident.span(), "A root `default` handler cannot be combined with spa(..), which serves its own fallback"),
+        (Some(action), None) => method_chain.extend( quote!{ .default_service( actix_web::web::to( #action ) ) } ),
+        (None, Some(_)) => {},
+        (None, None) => method_chain.extend( quote!{ .default_service( roolz::route::fallback::scope( prefix, ALLOWED_METHODS ) ) } )
+    }
+
+    let controllers = match &table.controllers {
+        Some(controllers) => quote!{ #controllers },
+        None => quote!{ crate::controllers }
+    };
+
+    // `prefix` is where the table is mounted, for the 405 responses of its scopes
+    let items = quote!{
+        #[allow(unused_variables)]
+        pub fn mount(config: &mut actix_web::web::ServiceConfig, prefix: &str) {
+            #allowed_methods
 
-    let expanded = quote!{
-        pub fn routes(config: &mut web::ServiceConfig) {
             config #method_chain;
 
         }
 
+        #route_info
+
+        #operations
+
+        #openapi_document
+
+        #paths_module
+
+        #route_table
+
+        #shadow_warnings
+    };
+
+    // a named table lives in a module of the same name next to its configuration function
+    let expanded = match &table.name {
+        Some(name) => quote!{
+            use #controllers::*;
+
+            pub fn #name(config: &mut actix_web::web::ServiceConfig) {
+                #name::mount(config, "")
+            }
+
+            pub mod #name {
+                #[allow(unused_imports)]
+                use super::*;
+
+                #items
+            }
+        },
+        None => quote!{
+            use #controllers::*;
+
+            pub fn routes(config: &mut actix_web::web::ServiceConfig) {
+                mount(config, "")
+            }
+
+            #items
+        }
     };
     TokenStream::from(expanded)
 }
 
+// routes!{ .. } or routes!(name [in controllers] => { .. })
+struct RouteTable {
+    name: Option<Ident>,
+    controllers: Option<Path>,
+    tree: RouteTree
+}
+
+impl Parse for RouteTable {
+    fn parse(stream: ParseStream) -> Result<Self> {
+        let mut name: Option<Ident> = None;
+        let mut controllers: Option<Path> = None;
+
+        if stream.peek(Ident) && (stream.peek2(Token![=>]) || stream.peek2(Token![in])) {
+            name = Some( stream.parse()? );
+        }
+
+        if stream.peek(Token![in]) {
+            let _in: Token![in] = stream.parse()?;
+            controllers = Some( stream.parse()? );
+        }
+
+        if name.is_none() && controllers.is_none() {
+            return Ok( RouteTable { name, controllers, tree: stream.parse()? } )
+        }
+
+        let _arrow: Token![=>] = stream.parse()?;
+        let within_table;
+        braced!(within_table in stream);
+
+        Ok( RouteTable { name, controllers, tree: within_table.parse()? } )
+    }
+}
+
 struct RouteTree {
-    scopes: Vec<Scope>
+    scopes: Vec<Scope>,
+    files: Vec<StaticFiles>,
+    spa: Option<Spa>,
+    openapi: Option<OpenApi>,
+    mounts: Vec<Mount>,
+    fallback: Option<Path>
 }
 
 struct Scope {
     path: LitStr,
+    clauses: Clauses,
     scopes: Vec<Scope>,
-    resources: Vec<Resource>
+    resources: Vec<Resource>,
+    redirects: Vec<Redirect>,
+    versions: Vec<VersionBlock>,
+    mounts: Vec<Mount>,
+    files: Vec<StaticFiles>,
+    // `default @ handler`, replacing the generated 404/405 responses of the scope and its nested scopes
+    fallback: Option<Path>
+}
+
+// routes served under several API versions, negotiated by header or a `/v{n}` path prefix
+struct VersionBlock {
+    versions: Versions,
+    scope: Scope
+}
+
+// version guard and deprecation headers of the services in a header negotiated version block
+#[derive(Default)]
+struct Negotiation {
+    guard: Option<proc_macro2::TokenStream>,
+    headers: Option<proc_macro2::TokenStream>
+}
+
+// optional clauses between a scope's path and its body, e.g. scope("/admin") wrap(RequireAdmin) data(config) { .. }
+#[derive(Default)]
+struct Clauses {
+    wraps: Vec<Expr>,
+    guards: Vec<Guard>,
+    data: Vec<Expr>,
+    rate_limits: Vec<RateLimit>,
+    timeout: Option<Timeout>,
+    cors: Option<Cors>
+}
+
+impl Clauses {
+    // guards can go on each service of the scope instead, middleware and data cannot
+    fn needs_scope(&self) -> bool {
+        !self.wraps.is_empty() || !self.data.is_empty() || !self.rate_limits.is_empty() ||
+            self.timeout.is_some() || self.cors.is_some()
+    }
 }
 
 struct Resource {
     method: Ident,
     path: LitStr,
-    action: Path
+    action: Path,
+    name: Option<Ident>,
+    clauses: RouteClauses
+}
+
+// optional clauses following a route's action, e.g. get "/" @ home guard(host = "example.com")
+#[derive(Default)]
+struct RouteClauses {
+    guards: Vec<Guard>,
+    api: Option<ApiDoc>,
+    rate_limits: Vec<RateLimit>,
+    timeout: Option<Timeout>
 }
 
 impl Parse for RouteTree {
     fn parse(stream: ParseStream) -> Result<Self> {
         let mut scopes: Vec<Scope> = Vec::new();
+        let mut files: Vec<StaticFiles> = Vec::new();
+        let mut spa: Option<Spa> = None;
+        let mut openapi: Option<OpenApi> = None;
+        let mut mounts: Vec<Mount> = Vec::new();
+        let mut fallback: Option<Path> = None;
 
         let mut within_scope;
         let mut path_stream;
         while !stream.is_empty(){
-            let _scope: Ident = stream.parse()?;
+            let keyword: Ident = stream.parse()?;
+
+            if keyword == "default" && !stream.peek(token::Paren) {
+                parse_at(stream)?;
+                fallback = Some( stream.parse()? );
+                continue;
+            }
+
             parenthesized!( path_stream in stream );
+
+            if keyword == "openapi" {
+                openapi = Some( path_stream.parse()? );
+                continue;
+            } else if keyword == "files" {
+                files.push( path_stream.parse()? );
+                continue;
+            } else if keyword == "spa" {
+                spa = Some( path_stream.parse()? );
+                continue;
+            } else if keyword == "mount" {
+                mounts.push( path_stream.parse()? );
+                continue;
+            }
+
+            let clauses: Clauses = parse_clauses(stream)?;
             braced!(within_scope in stream);
             let path: LitStr = path_stream.parse()?;
-            if let Ok(scp) = parse_scope(path, &within_scope) {
-                scopes.push( scp );
-            } else {
-                emit_error!(proc_macro2::Span::call_site(), "Malformatted route scope")
+            match parse_scope(path, &within_scope) {
+                Ok(scp) => scopes.push( Scope { clauses, ..scp } ),
+                Err(e) => emit_error!(e.span(), "Malformatted route scope: {}", e)
             }
         }
 
         Ok(
             RouteTree {
-                scopes
+                scopes,
+                files,
+                spa,
+                openapi,
+                mounts,
+                fallback
             }
         )
     }
@@ -72,6 +302,11 @@ impl Parse for RouteTree {
 fn parse_scope(path: LitStr, stream: ParseStream) -> Result<Scope> {
     let mut scopes: Vec<Scope> = Vec::new();
     let mut resources: Vec<Resource> = Vec::new();
+    let mut redirects: Vec<Redirect> = Vec::new();
+    let mut versions: Vec<VersionBlock> = Vec::new();
+    let mut mounts: Vec<Mount> = Vec::new();
+    let mut files: Vec<StaticFiles> = Vec::new();
+    let mut fallback: Option<Path> = None;
 
     while !stream.is_empty() {
         let is_scope = stream.peek2(token::Paren);
@@ -80,28 +315,62 @@ fn parse_scope(path: LitStr, stream: ParseStream) -> Result<Scope> {
         let path: LitStr;
 
         if is_scope {
-            let _scope: Ident = stream.parse()?;
+            let keyword: Ident = stream.parse()?;
             parenthesized!( path_stream in stream );
-            braced!(within_scope in stream);
+
+            if keyword == "files" {
+                files.push( path_stream.parse()? );
+                continue;
+            } else if keyword == "mount" {
+                mounts.push( path_stream.parse()? );
+                continue;
+            } else if keyword == "spa" {
+                return Err( syn::Error::new(keyword.span(), "spa(..) can only be declared at the root of the routes") )
+            } else if keyword == "version" {
+                versions.push( parse_version_block(&keyword, &path_stream, stream, &versions)? );
+                continue;
+            }
+
             path = path_stream.parse()?;
 
-            if let Ok(scp) = parse_scope(path, &within_scope) {
-                scopes.push( scp);
-            } else {
-                emit_error!(proc_macro2::Span::call_site(), "Malformatted route scope")
+            if keyword == "resources" {
+                parse_resources(keyword, path, stream, &mut scopes, &mut resources)?;
+                continue;
+            }
+
+            let clauses: Clauses = parse_clauses(stream)?;
+            braced!(within_scope in stream);
+
+            match parse_scope(path, &within_scope) {
+                Ok(scp) => scopes.push( Scope { clauses, ..scp } ),
+                Err(e) => emit_error!(e.span(), "Malformatted route scope: {}", e)
             }
 
         } else {
             let method: Ident = stream.parse()?;
+
+            if method == "redirect" {
+                redirects.push( stream.parse()? );
+                continue;
+            } else if method == "default" {
+                parse_at(stream)?;
+                fallback = Some( stream.parse()? );
+                continue;
+            }
+
             path = stream.parse()?;
-            let _at: Ident = stream.parse()?;
+            parse_at(stream)?;
             let action: Path = stream.parse()?;
+            let name: Option<Ident> = parse_name(stream)?;
+            let clauses: RouteClauses = parse_route_clauses(stream)?;
 
             resources.push(
                 Resource {
                     method,
                     path,
-                    action
+                    action,
+                    name,
+                    clauses
                 }
             );
         }
@@ -110,42 +379,940 @@ fn parse_scope(path: LitStr, stream: ParseStream) -> Result<Scope> {
     Ok(
         Scope {
             path,
+            clauses: Clauses::default(),
             scopes,
-            resources
+            resources,
+            redirects,
+            versions,
+            mounts,
+            files,
+            fallback
         }
     )
 }
 
-fn build_method_chain(scopes: Vec<Scope>) -> proc_macro2::TokenStream {
+// resources("posts") @ posts [as post] [only(..) | except(..)] [{ nested }]
+fn parse_resources(keyword: Ident, name: LitStr, stream: ParseStream, scopes: &mut Vec<Scope>, resources: &mut Vec<Resource>) -> Result<()> {
+    parse_at(stream)?;
+    let controller: Path = stream.parse()?;
+    let member_name: Option<Ident> = parse_name(stream)?;
+    let mut actions: Vec<&str> = RESOURCE_ACTIONS.to_vec();
+
+    if peek_keyword(stream, &["only", "except"]) {
+        let filter: Ident = stream.parse()?;
+        let filter_stream;
+        parenthesized!( filter_stream in stream );
+        let listed: Punctuated<Ident, Token![,]> = filter_stream.parse_terminated(Ident::parse)?;
+
+        for action in &listed {
+            if !RESOURCE_ACTIONS.iter().any(|a| action == a) {
+                emit_error!(action.span(), "Unknown resource action `{}`, expected one of: {}", action, RESOURCE_ACTIONS.join(", "));
+            }
+        }
+
+        if filter == "only" {
+            actions.retain(|a| listed.iter().any(|action| action == a));
+        } else {
+            actions.retain(|a| !listed.iter().any(|action| action == a));
+        }
+    }
+
+    let segment = name.value().trim_matches('/').to_string();
+    let span = name.span();
+    let collection = LitStr::new(&format!("/{}", segment), span);
+    let member = LitStr::new(&format!("/{}/{{id}}", segment), span);
+
+    let collection_name: Option<Ident> = member_name.as_ref().map(|n| Ident::new(&plural(&n.to_string()), n.span()));
+
+    for action in actions {
+        let (method, path, name) = match action {
+            "index" => ("get", &collection, &collection_name),
+            "show" => ("get", &member, &member_name),
+            "create" => ("post", &collection, &collection_name),
+            "update" => ("put", &member, &member_name),
+            _ => ("delete", &member, &member_name)
+        };
+
+        let mut action_path = controller.clone();
+        action_path.segments.push( Ident::new(action, keyword.span()).into() );
+
+        resources.push(
+            Resource {
+                method: Ident::new(method, keyword.span()),
+                path: path.clone(),
+                action: action_path,
+                name: name.clone(),
+                clauses: RouteClauses::default()
+            }
+        );
+    }
+
+    if stream.peek(token::Brace) {
+        let within_resource;
+        braced!(within_resource in stream);
+        let nested_path = LitStr::new(&format!("/{}/{{{}_id}}", segment, singular(&segment)), span);
+        let mut nested = parse_scope(nested_path, &within_resource)?;
+
+        // users as user { resources("posts") @ posts as post } names `user_post` and `user_posts`
+        let parent = member_name.as_ref().map(|n| n.to_string()).unwrap_or_else(|| singular(&segment));
+        prefix_names(&mut nested, &parent);
+
+        scopes.push(nested);
+    }
+
+    Ok(())
+}
+
+fn prefix_names(scope: &mut Scope, prefix: &str) {
+    for resource in &mut scope.resources {
+        if let Some(name) = &resource.name {
+            resource.name = Some( Ident::new(&format!("{}_{}", prefix, name), name.span()) );
+        }
+    }
+
+    for block in &mut scope.versions {
+        prefix_names(&mut block.scope, prefix);
+    }
+
+    for nested in &mut scope.scopes {
+        prefix_names(nested, prefix);
+    }
+}
+
+fn parse_version_block(keyword: &Ident, args: ParseStream, stream: ParseStream, siblings: &[VersionBlock]) -> Result<VersionBlock> {
+    let mut versions = Versions::parse(args, keyword)?;
+    versions.parse_deprecation(stream)?;
+
+    for number in &versions.numbers {
+        if siblings.iter().any(|block| block.versions.numbers.contains(number)) {
+            emit_error!(keyword.span(), "Version {} is already declared in this scope", number);
+        }
+    }
+
+    let within_block;
+    braced!(within_block in stream);
+    let scope = parse_scope(LitStr::new("", keyword.span()), &within_block)?;
+
+    if !scope.versions.is_empty() {
+        return Err( syn::Error::new(keyword.span(), "version(..) blocks cannot be nested") )
+    }
+
+    Ok( VersionBlock { versions, scope } )
+}
+
+fn parse_clauses(stream: ParseStream) -> Result<Clauses> {
+    let mut clauses = Clauses::default();
+
+    while !stream.peek(token::Brace) {
+        let keyword: Ident = stream.parse()?;
+        let args;
+        parenthesized!( args in stream );
+
+        if keyword == "wrap" {
+            let wraps: Punctuated<Expr, Token![,]> = args.parse_terminated(Expr::parse)?;
+            clauses.wraps.extend(wraps);
+        } else if keyword == "guard" {
+            clauses.guards.extend( guard::parse_guards(&args)? );
+        } else if keyword == "data" {
+            let data: Punctuated<Expr, Token![,]> = args.parse_terminated(Expr::parse)?;
+            clauses.data.extend(data);
+        } else if keyword == "rate_limit" {
+            clauses.rate_limits.push( args.parse()? );
+        } else if keyword == "timeout" {
+            clauses.timeout = Some( args.parse()? );
+        } else if keyword == "cors" {
+            clauses.cors = Some( args.parse()? );
+        } else {
+            return Err(
+                syn::Error::new(keyword.span(), format!("Unknown scope clause `{}`, expected one of: wrap, guard, data, rate_limit, timeout, cors", keyword))
+            )
+        }
+    }
+
+    Ok(clauses)
+}
+
+fn parse_route_clauses(stream: ParseStream) -> Result<RouteClauses> {
+    let mut clauses = RouteClauses::default();
+
+    while peek_keyword(stream, &["guard", "api", "rate_limit", "timeout"]) {
+        let keyword: Ident = stream.parse()?;
+        let args;
+        parenthesized!( args in stream );
+
+        if keyword == "guard" {
+            clauses.guards.extend( guard::parse_guards(&args)? );
+        } else if keyword == "rate_limit" {
+            clauses.rate_limits.push( args.parse()? );
+        } else if keyword == "timeout" {
+            clauses.timeout = Some( args.parse()? );
+        } else {
+            clauses.api = Some( args.parse()? );
+        }
+    }
+
+    Ok(clauses)
+}
+
+// accepts `@` between a route and its action, as well as the legacy bare word
+fn parse_at(stream: ParseStream) -> Result<()> {
+    if stream.peek(Token![@]) {
+        let _at: Token![@] = stream.parse()?;
+    } else {
+        let _at: Ident = stream.parse()?;
+    }
+
+    Ok(())
+}
+
+// true when the next tokens are one of `keywords` followed by a parenthesized group
+fn peek_keyword(stream: ParseStream, keywords: &[&str]) -> bool {
+    let fork = stream.fork();
+
+    match fork.parse::<Ident>() {
+        Ok(ident) => keywords.iter().any(|k| ident == k) && fork.peek(token::Paren),
+        Err(_) => false
+    }
+}
+
+// optional `as name` following a route's action
+fn parse_name(stream: ParseStream) -> Result<Option<Ident>> {
+    if stream.peek(Token![as]) {
+        let _as: Token![as] = stream.parse()?;
+        Ok(Some(stream.parse()?))
+    } else {
+        Ok(None)
+    }
+}
+
+fn plural(name: &str) -> String {
+    if let Some(stem) = name.strip_suffix('y') {
+        format!("{}ies", stem)
+    } else {
+        format!("{}s", name)
+    }
+}
+
+fn singular(name: &str) -> String {
+    if let Some(stem) = name.strip_suffix("ies") {
+        format!("{}y", stem)
+    } else if let Some(stem) = name.strip_suffix('s') {
+        stem.to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+fn build_method_chain(scopes: &[Scope], fallback: Option<&Path>) -> proc_macro2::TokenStream {
     let mut method_chain: Vec<proc_macro2::TokenStream> = Vec::new();
+    let routes = flatten_routes(scopes);
 
     for scope in scopes {
-        method_chain.push( build_scope_chain(&scope) );
+        // scope("/") would be an empty prefix claiming every path, files and the single page app
+        // included, so its services go on the app itself with its guards on each of them
+        if scope_path(&scope.path).value().is_empty() {
+            let guarded = !scope.clauses.guards.is_empty();
+
+            if scope.clauses.needs_scope() || scope.fallback.is_some() || (guarded && !scope.files.is_empty()) {
+                emit_error!(scope.path.span(), "scope(\"/\") at the root cannot take middleware, data, a default handler or guarded files, as it would claim every path; give it a prefix or declare them on the routes");
+            }
+
+            let guards: Vec<&proc_macro2::TokenStream> = scope.clauses.guards.iter().map(|g| &g.tokens).collect();
+            let negotiation = Negotiation { guard: all_guards(&guards), headers: None };
+            let full_path = path::join("", &scope.path.value());
+            method_chain.extend( build_scope_body(scope, &full_path, fallback, &routes, &negotiation) );
+            continue;
+        }
+
+        method_chain.push( build_scope_chain(scope, "", fallback, &routes, &Negotiation::default()) );
     }
 
     quote! { #( #method_chain )*  }
 
 }
 
-fn build_scope_chain(scope: &Scope) -> proc_macro2::TokenStream {
+// one guard passing when all of `guards` do, none for an empty list
+fn all_guards(guards: &[&proc_macro2::TokenStream]) -> Option<proc_macro2::TokenStream> {
+    let (first, rest) = guards.split_first()?;
+    Some( quote!{ actix_web::guard::All( #first ) #( .and( #rest ) )* } )
+}
+
+// a JsonConfig or PathConfig built in a `data(..)` clause replaces the root one, so it gets the
+// JSON errors too unless its builder chain sets an error handler of its own
+fn with_extractor_errors(data: &Expr) -> proc_macro2::TokenStream {
+    let mut expr = data;
+    let mut handled = false;
+
+    while let Expr::MethodCall(call) = expr {
+        handled |= call.method == "error_handler";
+        expr = &call.receiver;
+    }
+
+    // the type of e.g. `web::JsonConfig::default()`
+    let config = match expr {
+        Expr::Call(call) => match &*call.func {
+            Expr::Path(func) => func.path.segments.iter().rev().nth(1).map(|segment| segment.ident.to_string()),
+            _ => None
+        },
+        _ => None
+    };
+
+    match (config.as_deref(), handled) {
+        (Some("JsonConfig"), false) => quote!{ (#data).error_handler( roolz::route::extractors::json_error ) },
+        (Some("PathConfig"), false) => quote!{ (#data).error_handler( roolz::route::extractors::path_error ) },
+        _ => quote!{ #data }
+    }
+}
+
+fn build_scope_chain(scope: &Scope, prefix: &str, fallback: Option<&Path>, routes: &[FlatRoute], negotiation: &Negotiation) -> proc_macro2::TokenStream {
+    build_scope_service(scope, &scope.path, prefix, fallback, routes, negotiation)
+}
+
+// `path` differs from the scope's own for the `/v{n}` copies of version blocks
+fn build_scope_service(scope: &Scope, path: &LitStr, prefix: &str, fallback: Option<&Path>, routes: &[FlatRoute], negotiation: &Negotiation) -> proc_macro2::TokenStream {
     let mut method_chain: Vec<proc_macro2::TokenStream> = Vec::new();
-    let path = &scope.path;
+    let full_path = path::join(prefix, &path.value());
+    let path = scope_path(path);
+    // `default @ handler` applies to nested scopes as well
+    let fallback = scope.fallback.as_ref().or(fallback);
 
-    for scope in &scope.scopes {
-        method_chain.push( build_scope_chain( scope ) )
+    // extractor configs and shared state, overriding the defaults installed at the root
+    for data in &scope.clauses.data {
+        let data = with_extractor_errors(data);
+        method_chain.push( quote!{ .app_data( #data ) } )
     }
 
-    for resource in &scope.resources {
-        method_chain.push( build_resource_call( resource ) )
+    // actix runs the last registered middleware first, so reverse to run them in declared order
+    for wrap in scope.clauses.wraps.iter().rev() {
+        method_chain.push( quote!{ .wrap( #wrap ) } )
+    }
+
+    // outside of the scope's own middleware, so their time counts towards the limit
+    if let Some(timeout) = &scope.clauses.timeout {
+        let timeout = timeout.middleware();
+        method_chain.push( quote!{ .wrap( #timeout ) } )
+    }
+
+    // requests over the limit are turned away before any other middleware
+    for (i, rate_limit) in scope.clauses.rate_limits.iter().enumerate() {
+        let rate_limit = rate_limit.middleware( &format!("scope {} #{}", full_path, i) );
+        method_chain.push( quote!{ .wrap( #rate_limit ) } )
+    }
+
+    // registered last so every response of the scope carries the headers, 429s and timeouts included
+    if let Some(cors) = &scope.clauses.cors {
+        let cors = cors.middleware();
+        method_chain.push( quote!{ .wrap( #cors ) } )
+    }
+
+    for guard in &scope.clauses.guards {
+        let guard = &guard.tokens;
+        method_chain.push( quote!{ .guard( #guard ) } )
+    }
+
+    if let Some(guard) = &negotiation.guard {
+        method_chain.push( quote!{ .guard( #guard ) } )
+    }
+
+    if let Some(headers) = &negotiation.headers {
+        method_chain.push( quote!{ .wrap( #headers ) } )
     }
 
+    method_chain.extend( build_scope_body( scope, &full_path, fallback, routes, &Negotiation::default() ) );
+
+    method_chain.push(
+        match fallback {
+            Some(action) => quote!{ .default_service( actix_web::web::to( #action ) ) },
+            None => quote!{ .default_service( roolz::route::fallback::scope( prefix, ALLOWED_METHODS ) ) }
+        }
+    );
+
     quote!{ .service( actix_web::web::scope( #path ) #( #method_chain )*) }
 }
 
-fn build_resource_call(resource: &Resource) -> proc_macro2::TokenStream {
-    let path = &resource.path;
-    let method = &resource.method;
+// the services of a scope, without the scope itself; `negotiation` applies to each of them
+fn build_scope_body(scope: &Scope, full_path: &str, fallback: Option<&Path>, routes: &[FlatRoute], negotiation: &Negotiation) -> Vec<proc_macro2::TokenStream> {
+    let mut method_chain: Vec<proc_macro2::TokenStream> = Vec::new();
+
+    // resources first: a nested scope like `/users/{user_id}` would otherwise claim `/users/1`
+    for resource in &scope.resources {
+        method_chain.push( build_resource_call( resource, full_path, negotiation ) )
+    }
+
+    for redirect in &scope.redirects {
+        method_chain.push( redirect.route( &actix_path(&redirect.path) ) )
+    }
+
+    // requests without a version go to the latest one
+    let latest = scope.versions.iter().flat_map(|block| block.versions.numbers.iter()).max().copied();
+
+    for block in &scope.versions {
+        for &number in &block.versions.numbers {
+            let version = block.versions.guard(number, Some(number) == latest);
+            let negotiation = Negotiation {
+                guard: all_guards( &negotiation.guard.iter().chain(Some(&version)).collect::<Vec<_>>() ),
+                headers: block.versions.headers(number)
+            };
+            method_chain.extend( build_scope_body( &block.scope, full_path, fallback, routes, &negotiation ) );
+        }
+    }
+
+    method_chain.extend( build_implicit_routes( scope, full_path, routes, negotiation ) );
+
+    for nested in &scope.scopes {
+        method_chain.push( build_scope_chain( nested, full_path, fallback, routes, negotiation ) )
+    }
+
+    for mount in &scope.mounts {
+        method_chain.push( build_mount_call( mount, full_path, negotiation ) )
+    }
+
+    for block in &scope.versions {
+        for &number in &block.versions.numbers {
+            let version_path = LitStr::new(&format!("/v{}", number), block.scope.path.span());
+            let negotiation = Negotiation { guard: negotiation.guard.clone(), headers: block.versions.headers(number) };
+            method_chain.push( build_scope_service( &block.scope, &version_path, full_path, fallback, routes, &negotiation ) );
+        }
+    }
+
+    for files in &scope.files {
+        method_chain.push( files.service() )
+    }
+
+    method_chain
+}
+
+fn build_resource_call(resource: &Resource, full_path: &str, negotiation: &Negotiation) -> proc_macro2::TokenStream {
+    let path = actix_path(&resource.path);
+    let method = method_route(&resource.method);
     let action = &resource.action;
+    let guards = resource.clauses.guards.iter().map(|g| &g.tokens).chain(negotiation.guard.iter());
+    let wraps = route_wraps(resource, full_path, negotiation);
+
+    // `ws "/live" @ live::Session` routes a GET upgrade to a type implementing roolz's Channel
+    if is_websocket(resource) {
+        return quote!{
+            .route( #path, actix_web::web::get() #( .guard( #guards ) )* .to( roolz::route::channel::upgrade::<#action> ) #( .wrap( #wraps ) )* )
+        }
+    }
+
+    quote!{ .route( #path, #method #( .guard( #guards ) )* .to( #action ) #( .wrap( #wraps ) )* ) }
+}
+
+// timeout, rate limits of a route and the deprecation headers of its version, shared with its implicit HEAD
+fn route_wraps(resource: &Resource, full_path: &str, negotiation: &Negotiation) -> Vec<proc_macro2::TokenStream> {
+    let id = format!("{} {}", resource.method.to_string().to_uppercase(), path::join(full_path, &resource.path.value()));
+    let mut wraps: Vec<proc_macro2::TokenStream> = resource.clauses.timeout.iter().map(Timeout::middleware).collect();
+    // stacked limits, e.g. per minute and per day, each keep their own buckets
+    wraps.extend( resource.clauses.rate_limits.iter().enumerate().map(|(i, r)| r.middleware(&format!("{} #{}", id, i))) );
+    wraps.extend( negotiation.headers.iter().cloned() );
+    wraps
+}
+
+fn build_mount_call(mount: &Mount, full_path: &str, negotiation: &Negotiation) -> proc_macro2::TokenStream {
+    let path = scope_path(&mount.path);
+    let mount_path = path::join(full_path, &mount.path.value());
+    let mount_pattern = path::actix_pattern(&mount_path).unwrap_or(mount_path);
+    let table = &mount.table;
+    let guard = negotiation.guard.iter();
+    let headers = negotiation.headers.iter();
+
+    quote!{
+        .service(
+            actix_web::web::scope( #path ) #( .guard( #guard ) )* #( .wrap( #headers ) )*
+                .configure( |config| #table::mount( config, &format!( "{}{}", prefix, #mount_pattern ) ) )
+        )
+    }
+}
+
+// actix has no `web::options()` shorthand
+fn method_route(method: &Ident) -> proc_macro2::TokenStream {
+    if method == "options" {
+        quote!{ actix_web::web::method( actix_web::http::Method::OPTIONS ) }
+    } else {
+        quote!{ actix_web::web::#method() }
+    }
+}
+
+// HEAD for every GET route, answered by the same action as the server drops the body, and
+// OPTIONS for every path; explicitly declared head/options routes take precedence
+fn build_implicit_routes(scope: &Scope, full_path: &str, routes: &[FlatRoute], negotiation: &Negotiation) -> Vec<proc_macro2::TokenStream> {
+    let mut method_chain: Vec<proc_macro2::TokenStream> = Vec::new();
+    let mut answered: Vec<String> = Vec::new();
+
+    for resource in &scope.resources {
+        let route_path = path::join(full_path, &resource.path.value());
+
+        if resource.method == "get" && !is_declared(routes, "HEAD", &route_path) {
+            let path = actix_path(&resource.path);
+            let action = &resource.action;
+            let guards = resource.clauses.guards.iter().map(|g| &g.tokens).chain(negotiation.guard.iter());
+            let wraps = route_wraps(resource, full_path, negotiation);
+            method_chain.push( quote!{ .route( #path, actix_web::web::head() #( .guard( #guards ) )* .to( #action ) #( .wrap( #wraps ) )* ) } );
+        }
+    }
+
+    let declared = scope.resources.iter().map(|r| &r.path).chain( scope.redirects.iter().map(|r| &r.path) );
+
+    for declared in declared {
+        let route_path = path::join(full_path, &declared.value());
+        let path = actix_path(declared);
+        let normalized = path::normalize(&route_path);
+
+        if !answered.contains(&normalized) && !is_declared(routes, "OPTIONS", &route_path) {
+            let allow = allowed_methods(routes, &route_path).join(", ");
+            let guard = negotiation.guard.iter();
+            method_chain.push( quote!{ .route( #path, roolz::route::fallback::options( #allow ) #( .guard( #guard ) )* ) } );
+            answered.push(normalized);
+        }
+    }
+
+    method_chain
+}
+
+fn is_declared(routes: &[FlatRoute], method: &str, full_path: &str) -> bool {
+    routes.iter().any(|r| r.method == method && r.answers(full_path))
+}
+
+// methods answered on a path, in declaration order with the implicit HEAD and OPTIONS
+fn allowed_methods(routes: &[FlatRoute], full_path: &str) -> Vec<String> {
+    let mut methods: Vec<String> = Vec::new();
+
+    for route in routes.iter().filter(|r| r.answers(full_path)) {
+        let method = if route.is_websocket() { String::from("GET") } else { route.method.clone() };
+        let implicit_head = route.method == "GET";
+
+        if !methods.contains(&method) {
+            methods.push(method);
+        }
+
+        if implicit_head && !methods.iter().any(|m| m == "HEAD") {
+            methods.push( String::from("HEAD") );
+        }
+    }
+
+    methods.retain(|m| m != "OPTIONS");
+    methods.push( String::from("OPTIONS") );
+    methods
+}
+
+// method and actix pattern of every route, including implicit HEADs, for the 405 responses of
+// the scope fallbacks; a request may reach a scope whose own routes differ, e.g. `/users/1`
+// entering `/users/{user_id}`
+fn build_allowed_methods(scopes: &[Scope]) -> proc_macro2::TokenStream {
+    let mut methods: Vec<String> = Vec::new();
+    let mut patterns: Vec<String> = Vec::new();
+
+    for route in flatten_routes(scopes) {
+        let method = if route.is_websocket() { String::from("GET") } else { route.method.clone() };
+
+        for full_path in std::iter::once(&route.path).chain(route.alias.iter()) {
+            let pattern = path::actix_pattern(full_path).unwrap_or_else(|_| full_path.clone());
+
+            methods.push( method.clone() );
+            patterns.push( pattern.clone() );
+
+            if route.method == "GET" {
+                methods.push( String::from("HEAD") );
+                patterns.push( pattern );
+            }
+        }
+    }
+
+    quote!{
+        const ALLOWED_METHODS: &[(&str, &str)] = &[ #( (#methods, #patterns) ),* ];
+    }
+}
+
+// static prefixes of every scope and mount, which the single page app fallback must leave to the API
+fn api_prefixes(root: &RouteTree) -> Vec<String> {
+    let mut prefixes: Vec<String> = Vec::new();
+
+    fn collect(scope: &Scope, parent: &str, prefixes: &mut Vec<String>) {
+        let full_path = path::join(parent, &scope.path.value());
+        let prefix = full_path.split('{').next().unwrap_or("").trim_end_matches('/').to_string();
+
+        if !prefix.is_empty() && !prefixes.contains(&prefix) {
+            prefixes.push(prefix);
+        }
+
+        for nested in &scope.scopes {
+            collect(nested, &full_path, prefixes);
+        }
+    }
+
+    for scope in &root.scopes {
+        collect(scope, "", &mut prefixes);
+    }
+
+    for mount in &root.mounts {
+        let prefix = mount.path.value().split('{').next().unwrap_or("").trim_end_matches('/').to_string();
+
+        if !prefix.is_empty() && !prefixes.contains(&prefix) {
+            prefixes.push(prefix);
+        }
+    }
+
+    prefixes
+}
+
+fn is_websocket(resource: &Resource) -> bool {
+    resource.method == "ws"
+}
+
+fn actix_path(path: &LitStr) -> LitStr {
+    match path::actix_pattern(&path.value()) {
+        Ok(pattern) => LitStr::new(&pattern, path.span()),
+        Err(typ) => {
+            emit_error!(path.span(), "Unknown path parameter type `{}`, expected a primitive, String, uuid or regex(..)", typ);
+            path.clone()
+        }
+    }
+}
+
+// `path::join` drops the trailing `/` of a scope, so actix must not see it either: "/" becomes ""
+// and routes of scope("/") answer at "/route" rather than "//route"
+fn scope_path(path: &LitStr) -> LitStr {
+    actix_path( &LitStr::new(path.value().trim_end_matches('/'), path.span()) )
+}
+
+struct NamedPath {
+    name: Ident,
+    path: String
+}
+
+fn build_paths_module(scopes: &[Scope]) -> proc_macro2::TokenStream {
+    let mut named: Vec<NamedPath> = Vec::new();
+
+    for scope in scopes {
+        collect_named_paths(scope, "", &mut named);
+    }
+
+    let mut helpers: Vec<proc_macro2::TokenStream> = Vec::new();
+
+    for named_path in &named {
+        let name = &named_path.name;
+        let format_string = path::format_string(&named_path.path);
+        let mut params: Vec<Ident> = Vec::new();
+        let mut types: Vec<proc_macro2::TokenStream> = Vec::new();
+
+        for (param, typ) in path::param_types(&named_path.path) {
+            match syn::parse_str::<Ident>(&param) {
+                Ok(ident) => params.push( Ident::new(&ident.to_string(), name.span()) ),
+                Err(_) => emit_error!(name.span(), "Path parameter `{}` of route `{}` is not a valid identifier", param, name)
+            }
+
+            // numeric and boolean parameters keep their type, anything else only needs to display
+            types.push(
+                match typ.as_deref() {
+                    Some("String") | Some("str") | Some("uuid") | Some("Uuid") | None => quote!{ impl std::fmt::Display },
+                    Some(typ) => {
+                        let typ = Ident::new(typ, name.span());
+                        quote!{ #typ }
+                    }
+                }
+            );
+        }
+
+        helpers.push(
+            quote!{
+                pub fn #name( #( #params: #types ),* ) -> String {
+                    format!( #format_string #(, #params )* )
+                }
+            }
+        );
+    }
+
+    quote!{
+        pub mod paths {
+            #( #helpers )*
+        }
+    }
+}
+
+fn collect_named_paths(scope: &Scope, prefix: &str, named: &mut Vec<NamedPath>) {
+    let prefix = path::join(prefix, &scope.path.value());
 
-    quote!{ .route( #path, actix_web::web::#method().to( #action ) ) }
-}
\ No newline at end of file
+    for resource in &scope.resources {
+        if let Some(name) = &resource.name {
+            let full_path = path::join(&prefix, &resource.path.value());
+
+            match named.iter().find(|n| n.name == *name) {
+                Some(existing) if existing.path == full_path => {},
+                Some(existing) => emit_error!(name.span(), "Route name `{}` is already used for \"{}\"", name, existing.path),
+                None => named.push( NamedPath { name: name.clone(), path: full_path } )
+            }
+        }
+    }
+
+    // named routes of version blocks point at the header negotiated path
+    for block in &scope.versions {
+        collect_named_paths(&block.scope, &prefix, named);
+    }
+
+    for scope in &scope.scopes {
+        collect_named_paths(scope, &prefix, named);
+    }
+}
+
+// every route of the tree with its full path, in registration order
+struct FlatRoute<'a> {
+    method: String,
+    path: String,
+    // guards of the route and all enclosing scopes
+    guards: Vec<String>,
+    // full path without the `/v{n}` prefix of routes in version blocks
+    alias: Option<String>,
+    // None for redirects
+    resource: Option<&'a Resource>,
+    // the path as declared, for diagnostics
+    declared: &'a LitStr
+}
+
+impl FlatRoute<'_> {
+    fn answers(&self, full_path: &str) -> bool {
+        let normalized = path::normalize(full_path);
+        path::normalize(&self.path) == normalized || self.alias.iter().any(|alias| path::normalize(alias) == normalized)
+    }
+
+    fn is_websocket(&self) -> bool {
+        self.resource.map_or(false, is_websocket)
+    }
+}
+
+fn flatten_routes(scopes: &[Scope]) -> Vec<FlatRoute<'_>> {
+    let mut routes: Vec<FlatRoute> = Vec::new();
+
+    for scope in scopes {
+        flatten_scope(scope, "", None, &[], &mut routes);
+    }
+
+    routes
+}
+
+fn flatten_scope<'a>(scope: &'a Scope, prefix: &str, alias: Option<&str>, guards: &[String], routes: &mut Vec<FlatRoute<'a>>) {
+    let prefix = path::join(prefix, &scope.path.value());
+    let alias = alias.map(|alias| path::join(alias, &scope.path.value()));
+    let mut scope_guards: Vec<String> = guards.to_vec();
+    scope_guards.extend( scope.clauses.guards.iter().map(|g| g.key.clone()) );
+
+    for resource in &scope.resources {
+        let mut route_guards = scope_guards.clone();
+        route_guards.extend( resource.clauses.guards.iter().map(|g| g.key.clone()) );
+        route_guards.sort();
+
+        routes.push(
+            FlatRoute {
+                method: resource.method.to_string().to_uppercase(),
+                path: path::join(&prefix, &resource.path.value()),
+                guards: route_guards,
+                alias: alias.as_ref().map(|alias| path::join(alias, &resource.path.value())),
+                resource: Some(resource),
+                declared: &resource.path
+            }
+        );
+    }
+
+    // redirects answer GET and HEAD
+    for redirect in &scope.redirects {
+        let mut route_guards = scope_guards.clone();
+        route_guards.sort();
+
+        routes.push(
+            FlatRoute {
+                method: String::from("GET"),
+                path: path::join(&prefix, &redirect.path.value()),
+                guards: route_guards,
+                alias: alias.as_ref().map(|alias| path::join(alias, &redirect.path.value())),
+                resource: None,
+                declared: &redirect.path
+            }
+        );
+    }
+
+    for block in &scope.versions {
+        for number in &block.versions.numbers {
+            let version_prefix = path::join(&prefix, &format!("/v{}", number));
+            flatten_scope(&block.scope, &version_prefix, Some(&prefix), &scope_guards, routes);
+        }
+    }
+
+    for scope in &scope.scopes {
+        flatten_scope(scope, &prefix, alias.as_deref(), &scope_guards, routes);
+    }
+}
+
+fn build_route_table(scopes: &[Scope]) -> proc_macro2::TokenStream {
+    let mut entries: Vec<proc_macro2::TokenStream> = Vec::new();
+
+    for route in flatten_routes(scopes) {
+        let method = &route.method;
+        let path = &route.path;
+        let action = match route.resource {
+            Some(resource) => resource.action.segments.iter().
+                map(|segment| segment.ident.to_string()).
+                collect::<Vec<String>>().
+                join("::"),
+            None => String::from("redirect")
+        };
+        let line = quote_spanned!{ route.declared.span()=> line!() };
+
+        entries.push(
+            quote!{
+                roolz::route::RouteInfo {
+                    method: #method,
+                    path: std::borrow::Cow::Borrowed( #path ),
+                    action: #action,
+                    line: #line
+                }
+            }
+        );
+    }
+
+    quote!{
+        pub static ROUTES: &[roolz::route::RouteInfo] = &[
+            #( #entries ),*
+        ];
+    }
+}
+
+// the route table merged with those of mounted tables, prefixed with their mount path
+fn build_route_info(root: &RouteTree) -> proc_macro2::TokenStream {
+    let mut mounts: Vec<(String, &Mount)> = root.mounts.iter().map(|mount| (mount.path.value(), mount)).collect();
+
+    for scope in &root.scopes {
+        collect_mounts(scope, "", &mut mounts);
+    }
+
+    if mounts.is_empty() {
+        return quote!{
+            pub fn route_info() -> Vec<roolz::route::RouteInfo> {
+                ROUTES.to_vec()
+            }
+        }
+    }
+
+    let paths = mounts.iter().map(|(path, _)| path);
+    let tables = mounts.iter().map(|(_, mount)| &mount.table);
+
+    quote!{
+        pub fn route_info() -> Vec<roolz::route::RouteInfo> {
+            let mut routes: Vec<roolz::route::RouteInfo> = ROUTES.to_vec();
+            #( routes.extend( roolz::route::prefixed( #paths, #tables::route_info() ) ); )*
+            routes
+        }
+    }
+}
+
+fn collect_mounts<'a>(scope: &'a Scope, prefix: &str, mounts: &mut Vec<(String, &'a Mount)>) {
+    let prefix = path::join(prefix, &scope.path.value());
+
+    for mount in &scope.mounts {
+        mounts.push( (path::join(&prefix, &mount.path.value()), mount) );
+    }
+
+    for block in &scope.versions {
+        for number in &block.versions.numbers {
+            collect_mounts(&block.scope, &path::join(&prefix, &format!("/v{}", number)), mounts);
+        }
+    }
+
+    for scope in &scope.scopes {
+        collect_mounts(scope, &prefix, mounts);
+    }
+}
+
+// exact duplicates are errors; routes unreachable behind an earlier dynamic segment are warnings
+fn check_route_conflicts(scopes: &[Scope]) -> proc_macro2::TokenStream {
+    let routes = flatten_routes(scopes);
+    let mut warnings: Vec<proc_macro2::TokenStream> = Vec::new();
+
+    for (i, route) in routes.iter().enumerate() {
+        let span = route.declared.span();
+        // differently guarded routes may legitimately share a method and path
+        let earlier = routes[..i].iter().filter(|r| r.method == route.method && r.guards == route.guards);
+
+        for previous in earlier {
+            if path::normalize(&previous.path) == path::normalize(&route.path) {
+                emit_error!(
+                    span, "Duplicate route {} {}", route.method, route.path;
+                    note = previous.declared.span() => "first declared as {} {}", previous.method, previous.path
+                );
+                break;
+            } else if path::shadows(&previous.path, &route.path) {
+                let message = format!(
+                    "route {} {} is shadowed by {} {} declared before it and will never match",
+                    route.method, route.path, previous.method, previous.path
+                );
+                warnings.push( warning(span, &message) );
+                break;
+            }
+        }
+    }
+
+    quote!{ #( #warnings )* }
+}
+
+// stable proc macros cannot emit warnings directly, so use a deprecated item at the offending span
+fn warning(span: proc_macro2::Span, message: &str) -> proc_macro2::TokenStream {
+    quote_spanned!{ span=>
+        const _: () = {
+            #[deprecated(note = #message)]
+            #[allow(non_camel_case_types)]
+            struct route_warning;
+            let _ = route_warning;
+        };
+    }
+}
+
+// every table lists its operations so that the document of a table mounting it can include them;
+// handlers are only inferred under openapi(..), as inference requires every handler argument to
+// implement `roolz::route::openapi::Extractor`
+fn build_operations(root: &RouteTree) -> proc_macro2::TokenStream {
+    let infer = root.openapi.is_some();
+    let routes = flatten_routes(&root.scopes);
+    // redirects and websockets have no operation to describe
+    let http_routes = routes.into_iter().enumerate().filter_map(|(i, route)| {
+        route.resource.filter(|resource| !is_websocket(resource)).map(|resource| (i, resource))
+    });
+
+    let operations = http_routes.map(|(i, resource)| {
+        let operation = openapi::operation(&resource.action, resource.clauses.api.as_ref(), infer);
+        quote!{ ( ROUTES[#i].clone(), #operation ) }
+    });
+
+    let mut mounts: Vec<(String, &Mount)> = root.mounts.iter().map(|mount| (mount.path.value(), mount)).collect();
+
+    for scope in &root.scopes {
+        collect_mounts(scope, "", &mut mounts);
+    }
+
+    let paths = mounts.iter().map(|(path, _)| path);
+    let tables = mounts.iter().map(|(_, mount)| &mount.table);
+
+    quote!{
+        #[allow(unused_mut)]
+        pub fn operations() -> Vec<(roolz::route::RouteInfo, roolz::route::openapi::Operation)> {
+            let mut operations: Vec<(roolz::route::RouteInfo, roolz::route::openapi::Operation)> = vec![ #( #operations ),* ];
+            #( operations.extend( roolz::route::openapi::prefixed( #paths, #tables::operations() ) ); )*
+            operations
+        }
+    }
+}
+
+fn build_openapi_document(root: &RouteTree) -> proc_macro2::TokenStream {
+    let openapi = match &root.openapi {
+        Some(openapi) => openapi,
+        None => return proc_macro2::TokenStream::new()
+    };
+
+    let document = openapi.document();
+
+    quote!{
+        pub fn openapi() -> roolz::route::openapi::Value {
+            #document
+                .operations( operations() )
+                .to_json()
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use {
+    syn::{Path, LitStr, Token},
+    syn::parse::{Parse, ParseStream, Result}
+};
+
+// mount("/admin", crate::admin::admin_routes) nests a route table declared elsewhere, named
+// by the module holding its `mount`, `route_info` and `operations` functions
+pub struct Mount {
+    pub path: LitStr,
+    pub table: Path
+}
+
+impl Parse for Mount {
+    fn parse(stream: ParseStream) -> Result<Self> {
+        let path: LitStr = stream.parse()?;
+        let _comma: Token![,] = stream.parse()?;
+        let table: Path = stream.parse()?;
+
+        Ok( Mount { path, table } )
+    }
+}
@@ -0,0 +1,134 @@
+use {
+    syn::{Path, LitStr, LitInt, Ident, Type, Token},
+    syn::parse::{Parse, ParseStream, Result}
+};
+
+// openapi(["/openapi.json"] [, title = "..."] [, version = "..."]) at the root of the route tree
+pub struct OpenApi {
+    pub path: Option<LitStr>,
+    title: Option<LitStr>,
+    version: Option<LitStr>
+}
+
+impl Parse for OpenApi {
+    fn parse(stream: ParseStream) -> Result<Self> {
+        let mut openapi = OpenApi { path: None, title: None, version: None };
+
+        if stream.peek(LitStr) {
+            openapi.path = Some(stream.parse()?);
+            parse_comma(stream)?;
+        }
+
+        while !stream.is_empty() {
+            let key: Ident = stream.parse()?;
+            let _eq: Token![=] = stream.parse()?;
+
+            if key == "title" {
+                openapi.title = Some(stream.parse()?);
+            } else if key == "version" {
+                openapi.version = Some(stream.parse()?);
+            } else {
+                return Err( syn::Error::new(key.span(), format!("Unknown openapi option `{}`, expected `title` or `version`", key)) )
+            }
+
+            parse_comma(stream)?;
+        }
+
+        Ok(openapi)
+    }
+}
+
+impl OpenApi {
+    pub fn document(&self) -> proc_macro2::TokenStream {
+        let title = match &self.title {
+            Some(title) => quote!{ #title },
+            None => quote!{ env!("CARGO_PKG_NAME") }
+        };
+
+        let version = match &self.version {
+            Some(version) => quote!{ #version },
+            None => quote!{ env!("CARGO_PKG_VERSION") }
+        };
+
+        quote!{ roolz::route::openapi::Document::new( #title, #version ) }
+    }
+}
+
+// api(request = Type, response = Type, status = 201, summary = "...") on a route; responses are
+// never inferred, and requests only from the handler's extractors
+#[derive(Default)]
+pub struct ApiDoc {
+    request: Option<Type>,
+    response: Option<Type>,
+    status: Option<LitInt>,
+    summary: Option<LitStr>
+}
+
+impl Parse for ApiDoc {
+    fn parse(stream: ParseStream) -> Result<Self> {
+        let mut doc = ApiDoc::default();
+
+        while !stream.is_empty() {
+            let key: Ident = stream.parse()?;
+            let _eq: Token![=] = stream.parse()?;
+
+            if key == "request" {
+                doc.request = Some(stream.parse()?);
+            } else if key == "response" {
+                doc.response = Some(stream.parse()?);
+            } else if key == "status" {
+                doc.status = Some(stream.parse()?);
+            } else if key == "summary" {
+                doc.summary = Some(stream.parse()?);
+            } else {
+                return Err(
+                    syn::Error::new(key.span(), format!("Unknown api option `{}`, expected one of: request, response, status, summary", key))
+                )
+            }
+
+            parse_comma(stream)?;
+        }
+
+        Ok(doc)
+    }
+}
+
+// the `Operation` describing one route: inferred from the handler, when `infer` is set, unless a request type is given
+pub fn operation(action: &Path, doc: Option<&ApiDoc>, infer: bool) -> proc_macro2::TokenStream {
+    let inferred = match infer {
+        true => quote!{ roolz::route::openapi::describe( &#action ) },
+        false => quote!{ roolz::route::openapi::Operation::default() }
+    };
+
+    let doc = match doc {
+        Some(doc) => doc,
+        None => return inferred
+    };
+
+    let mut operation = match &doc.request {
+        Some(request) => quote!{ roolz::route::openapi::Operation::default().request::<#request>() },
+        None => inferred
+    };
+
+    if let Some(response) = &doc.response {
+        let status = match &doc.status {
+            Some(status) => quote!{ #status },
+            None => quote!{ 200 }
+        };
+        operation = quote!{ #operation.response::<#response>( #status ) };
+    }
+
+    if let Some(summary) = &doc.summary {
+        operation = quote!{ #operation.summary( #summary ) };
+    }
+
+    operation
+}
+
+fn parse_comma(stream: ParseStream) -> Result<()> {
+    if !stream.is_empty() {
+        let _comma: Token![,] = stream.parse()?;
+    }
+
+    Ok(())
+}
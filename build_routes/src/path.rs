@@ -0,0 +1,225 @@
+// helpers for working with actix path patterns, e.g. "/users/{user_id}/posts/{id}"
+
+pub fn join(prefix: &str, path: &str) -> String {
+    let joined = format!("{}{}", prefix.trim_end_matches('/'), path);
+
+    if joined.is_empty() {
+        String::from("/")
+    } else {
+        joined
+    }
+}
+
+// names of the dynamic segments, in order of appearance
+pub fn params(path: &str) -> Vec<String> {
+    segments(path).into_iter().filter_map(|segment| {
+        match segment {
+            Segment::Dynamic(name, _) => Some(name),
+            Segment::Static(_) => None
+        }
+    }).collect()
+}
+
+// the pattern with every dynamic segment replaced by `{}`, for use with `format!`
+pub fn format_string(path: &str) -> String {
+    segments(path).into_iter().map(|segment| {
+        match segment {
+            Segment::Dynamic(_, _) => String::from("{}"),
+            Segment::Static(text) => text.replace('{', "{{").replace('}', "}}")
+        }
+    }).collect()
+}
+
+// the pattern with parameter names erased, so "/posts/{id}" and "/posts/{post_id}" compare equal
+pub fn normalize(path: &str) -> String {
+    segments(path).into_iter().map(|segment| {
+        match segment {
+            Segment::Dynamic(_, Some(pattern)) => format!("{{:{}}}", pattern),
+            Segment::Dynamic(_, None) => String::from("{}"),
+            Segment::Static(text) => text
+        }
+    }).collect()
+}
+
+// the rust type of each typed parameter, e.g. ("id", Some("i32")) for {id:i32}
+pub fn param_types(path: &str) -> Vec<(String, Option<String>)> {
+    segments(path).into_iter().filter_map(|segment| {
+        match segment {
+            Segment::Dynamic(name, Some(pattern)) if type_regex(&pattern).is_some() => Some((name, Some(pattern))),
+            Segment::Dynamic(name, _) => Some((name, None)),
+            Segment::Static(_) => None
+        }
+    }).collect()
+}
+
+// rewrites {id:i32} and {slug:regex([a-z-]+)} into actix regex segments; plain actix
+// patterns such as {id:\d+} pass through, unknown types are returned as the error
+pub fn actix_pattern(path: &str) -> std::result::Result<String, String> {
+    let mut pattern = String::new();
+
+    for segment in segments(path) {
+        match segment {
+            Segment::Static(text) => pattern.push_str(&text),
+            Segment::Dynamic(name, None) => pattern.push_str(&format!("{{{}}}", name)),
+            Segment::Dynamic(name, Some(typ)) => {
+                let regex = if let Some(regex) = typ.strip_prefix("regex(").and_then(|r| r.strip_suffix(')')) {
+                    regex.to_string()
+                } else if let Some(regex) = type_regex(&typ) {
+                    regex.to_string()
+                } else if is_typed(&typ) {
+                    return Err(typ)
+                } else {
+                    typ
+                };
+
+                pattern.push_str(&format!("{{{}:{}}}", name, regex));
+            }
+        }
+    }
+
+    Ok(pattern)
+}
+
+// a bare identifier after the `:` names a type rather than a raw regex
+fn is_typed(pattern: &str) -> bool {
+    pattern.starts_with("regex(") || syn::parse_str::<syn::Ident>(pattern).is_ok()
+}
+
+fn type_regex(typ: &str) -> Option<&'static str> {
+    match typ {
+        "i8" | "i16" | "i32" | "i64" | "i128" | "isize" => Some("-?[0-9]+"),
+        "u8" | "u16" | "u32" | "u64" | "u128" | "usize" => Some("[0-9]+"),
+        "f32" | "f64" => Some("-?[0-9]+(?:\\.[0-9]+)?"),
+        "bool" => Some("true|false"),
+        "uuid" | "Uuid" => Some("[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}"),
+        "String" | "str" => Some("[^/]+"),
+        _ => None
+    }
+}
+
+// true when every request `later` would match is already matched by `earlier`,
+// e.g. "/posts/{id}" shadows "/posts/new"
+pub fn shadows(earlier: &str, later: &str) -> bool {
+    let earlier_parts: Vec<&str> = earlier.split('/').collect();
+    let later_parts: Vec<&str> = later.split('/').collect();
+
+    if earlier_parts.len() != later_parts.len() {
+        return false
+    }
+
+    earlier_parts.iter().zip(later_parts.iter()).all(|(e, l)| {
+        e == l || (is_plain_param(e) && !l.contains('{'))
+    })
+}
+
+// a whole segment that is a single parameter without a custom pattern
+fn is_plain_param(part: &str) -> bool {
+    part.starts_with('{') && part.ends_with('}') && !part.contains(':') && part.matches('{').count() == 1
+}
+
+pub enum Segment {
+    Static(String),
+    // name, and the pattern or type after the `:`
+    Dynamic(String, Option<String>)
+}
+
+pub fn segments(path: &str) -> Vec<Segment> {
+    let mut segments: Vec<Segment> = Vec::new();
+    let mut rest = path;
+
+    while let Some(start) = rest.find('{') {
+        if start > 0 {
+            segments.push( Segment::Static(rest[..start].to_string()) );
+        }
+
+        let end = match closing_brace(&rest[start..]) {
+            Some(end) => start + end,
+            None => break
+        };
+
+        let inner = &rest[start + 1..end];
+        let segment = match inner.find(':') {
+            Some(colon) => Segment::Dynamic(inner[..colon].trim().to_string(), Some(inner[colon + 1..].trim().to_string())),
+            None => Segment::Dynamic(inner.trim().to_string(), None)
+        };
+
+        segments.push( segment );
+        rest = &rest[end + 1..];
+    }
+
+    if !rest.is_empty() {
+        segments.push( Segment::Static(rest.to_string()) );
+    }
+
+    segments
+}
+
+// regex patterns may themselves contain braces, e.g. {code:\d{3}}
+fn closing_brace(text: &str) -> Option<usize> {
+    let mut depth = 0;
+
+    for (i, c) in text.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i)
+                }
+            },
+            _ => {}
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_segments() {
+        let segments = segments("/posts/{id:i32}/code/{code:\\d{3}}");
+        let described: Vec<String> = segments.iter().map(|segment| match segment {
+            Segment::Static(text) => format!("static {}", text),
+            Segment::Dynamic(name, pattern) => format!("dynamic {} {:?}", name, pattern)
+        }).collect();
+
+        assert_eq!(described, vec![
+            "static /posts/", "dynamic id Some(\"i32\")", "static /code/", "dynamic code Some(\"\\\\d{3}\")"
+        ]);
+        assert_eq!(params("/users/{user_id}/posts/{id}"), vec!["user_id", "id"]);
+    }
+
+    #[test]
+    fn rewrites_typed_params() {
+        assert_eq!(actix_pattern("/posts/{id:i32}").unwrap(), "/posts/{id:-?[0-9]+}");
+        assert_eq!(actix_pattern("/posts/{slug:regex([a-z-]+)}").unwrap(), "/posts/{slug:[a-z-]+}");
+        assert_eq!(actix_pattern("/posts/{id:\\d+}/{rest}").unwrap(), "/posts/{id:\\d+}/{rest}");
+        assert_eq!(actix_pattern("/posts/{id:Money}").unwrap_err(), "Money");
+    }
+
+    #[test]
+    fn normalizes_param_names() {
+        assert_eq!(normalize("/posts/{id}"), normalize("/posts/{post_id}"));
+        assert_eq!(normalize("/posts/{id:i32}"), "/posts/{:i32}");
+        assert_ne!(normalize("/posts/{id:i32}"), normalize("/posts/{id}"));
+    }
+
+    #[test]
+    fn detects_shadowed_paths() {
+        assert!(shadows("/posts/{id}", "/posts/new"));
+        assert!(shadows("/posts/new", "/posts/new"));
+        assert!(!shadows("/posts/new", "/posts/{id}"));
+        assert!(!shadows("/posts/{id:\\d+}", "/posts/new"));
+        assert!(!shadows("/posts/{id}", "/posts/new/edit"));
+    }
+
+    #[test]
+    fn joins_and_formats() {
+        assert_eq!(join("/api", "/posts"), "/api/posts");
+        assert_eq!(join("", ""), "/");
+        assert_eq!(format_string("/users/{user_id}/posts/{id:i32}"), "/users/{}/posts/{}");
+    }
+}
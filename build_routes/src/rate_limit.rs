@@ -0,0 +1,87 @@
+use {
+    syn::{LitStr, LitInt, Ident, Expr, Token, parenthesized},
+    syn::parse::{Parse, ParseStream, Result}
+};
+
+// rate_limit(10 per minute by ip [, store = RedisStore::new(..)]), clients being told apart
+// `by ip`, `by real_ip` or `by header("X-Api-Key")`
+pub struct RateLimit {
+    requests: LitInt,
+    seconds: u64,
+    key: proc_macro2::TokenStream,
+    store: Option<Expr>
+}
+
+impl Parse for RateLimit {
+    fn parse(stream: ParseStream) -> Result<Self> {
+        let requests: LitInt = stream.parse()?;
+        expect_word(stream, "per")?;
+
+        let unit: Ident = stream.parse()?;
+        let seconds = match unit.to_string().trim_end_matches('s') {
+            "second" => 1,
+            "minute" => 60,
+            "hour" => 3600,
+            "day" => 86400,
+            _ => return Err( syn::Error::new(unit.span(), format!("Unknown rate limit period `{}`, expected second, minute, hour or day", unit)) )
+        };
+
+        expect_word(stream, "by")?;
+
+        let by: Ident = stream.parse()?;
+        let key = if by == "ip" {
+            quote!{ roolz::route::rate_limit::Key::Ip }
+        } else if by == "real_ip" {
+            quote!{ roolz::route::rate_limit::Key::RealIp }
+        } else if by == "header" {
+            let args;
+            parenthesized!( args in stream );
+            let header: LitStr = args.parse()?;
+            quote!{ roolz::route::rate_limit::Key::Header( #header ) }
+        } else {
+            return Err( syn::Error::new(by.span(), format!("Unknown rate limit key `{}`, expected ip, real_ip or header(..)", by)) )
+        };
+
+        let mut store: Option<Expr> = None;
+
+        if stream.peek(Token![,]) {
+            let _comma: Token![,] = stream.parse()?;
+            expect_word(stream, "store")?;
+            let _eq: Token![=] = stream.parse()?;
+            store = Some(stream.parse()?);
+        }
+
+        Ok( RateLimit { requests, seconds, key, store } )
+    }
+}
+
+impl RateLimit {
+    // limiters with the same id share their buckets, e.g. a GET route and its implicit HEAD, so
+    // the id tells apart the clauses stacked on one route or scope
+    pub fn middleware(&self, id: &str) -> proc_macro2::TokenStream {
+        let requests = &self.requests;
+        let seconds = self.seconds;
+        let key = &self.key;
+        let store = self.store.iter();
+
+        quote!{
+            {
+                let id = concat!( module_path!(), " ", #id );
+                roolz::route::rate_limit::shared( id, || {
+                    roolz::route::rate_limit::RateLimit::new( id, #requests, std::time::Duration::from_secs( #seconds ), #key )
+                        #( .store( #store ) )*
+                })
+            }
+        }
+    }
+}
+
+fn expect_word(stream: ParseStream, word: &str) -> Result<()> {
+    let ident: Ident = stream.parse()?;
+
+    if ident != word {
+        return Err( syn::Error::new(ident.span(), format!("Expected `{}`", word)) )
+    }
+
+    Ok(())
+}
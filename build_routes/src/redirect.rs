@@ -0,0 +1,57 @@
+use {
+    syn::{LitStr, LitInt, Ident, Token},
+    syn::parse::{Parse, ParseStream, Result},
+    proc_macro_error::emit_error,
+    crate::path
+};
+
+// redirect "/old/{id}" => "/new/{id}" [permanent | temporary | 301 | 302 | 303 | 307 | 308]
+pub struct Redirect {
+    pub path: LitStr,
+    target: LitStr,
+    status: u16
+}
+
+impl Parse for Redirect {
+    fn parse(stream: ParseStream) -> Result<Self> {
+        let path: LitStr = stream.parse()?;
+        let _arrow: Token![=>] = stream.parse()?;
+        let target: LitStr = stream.parse()?;
+
+        let status = if stream.peek(LitInt) {
+            let code: LitInt = stream.parse()?;
+            let status: u16 = code.base10_parse()?;
+            if ![301, 302, 303, 307, 308].contains(&status) {
+                return Err( syn::Error::new(code.span(), "Redirect status must be one of 301, 302, 303, 307 or 308") )
+            }
+            status
+        } else if stream.peek(Ident) && (peek_word(stream, "permanent") || peek_word(stream, "temporary")) {
+            let kind: Ident = stream.parse()?;
+            if kind == "permanent" { 301 } else { 302 }
+        } else {
+            302
+        };
+
+        let source_params = path::params(&path.value());
+        for param in path::params(&target.value()) {
+            if !source_params.contains(&param) {
+                emit_error!(target.span(), "Redirect target uses `{{{}}}`, which is not a parameter of \"{}\"", param, path.value());
+            }
+        }
+
+        Ok( Redirect { path, target, status } )
+    }
+}
+
+impl Redirect {
+    pub fn route(&self, actix_path: &LitStr) -> proc_macro2::TokenStream {
+        let target = &self.target;
+        let status = self.status;
+
+        quote!{ .route( #actix_path, roolz::route::redirect::to( #target, #status ) ) }
+    }
+}
+
+fn peek_word(stream: ParseStream, word: &str) -> bool {
+    stream.fork().parse::<Ident>().map(|ident| ident == word).unwrap_or(false)
+}
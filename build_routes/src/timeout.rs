@@ -0,0 +1,59 @@
+use {
+    syn::LitInt,
+    syn::parse::{Parse, ParseStream, Result}
+};
+
+// timeout(5s) or timeout(500ms)
+pub struct Timeout {
+    millis: u64
+}
+
+impl Parse for Timeout {
+    fn parse(stream: ParseStream) -> Result<Self> {
+        let limit: LitInt = stream.parse()?;
+        let value: u64 = limit.base10_parse()?;
+
+        let millis = match limit.suffix() {
+            "s" => match value.checked_mul(1000) {
+                Some(millis) => millis,
+                None => return Err( syn::Error::new(limit.span(), format!("Timeout of {}s is too long", value)) )
+            },
+            "ms" => value,
+            "" => return Err( syn::Error::new(limit.span(), format!("Missing timeout unit, e.g. `{}s` or `{}ms`", value, value)) ),
+            unit => return Err( syn::Error::new(limit.span(), format!("Unknown timeout unit `{}`, expected s or ms", unit)) )
+        };
+
+        if millis == 0 {
+            return Err( syn::Error::new(limit.span(), "Timeouts must be longer than zero") )
+        }
+
+        Ok( Timeout { millis } )
+    }
+}
+
+impl Timeout {
+    pub fn middleware(&self) -> proc_macro2::TokenStream {
+        let millis = self.millis;
+        quote!{ roolz::route::timeout::Timeout::new( std::time::Duration::from_millis( #millis ) ) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_seconds_and_millis() {
+        assert_eq!(syn::parse_str::<Timeout>("5s").unwrap().millis, 5000);
+        assert_eq!(syn::parse_str::<Timeout>("500ms").unwrap().millis, 500);
+    }
+
+    #[test]
+    fn rejects_invalid_timeouts() {
+        assert!(syn::parse_str::<Timeout>("5").is_err());
+        assert!(syn::parse_str::<Timeout>("5m").is_err());
+        assert!(syn::parse_str::<Timeout>("0ms").is_err());
+        assert!(syn::parse_str::<Timeout>("18446744073709551615s").is_err());
+        assert!(syn::parse_str::<Timeout>("18446744073709551616ms").is_err());
+    }
+}
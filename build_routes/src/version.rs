@@ -0,0 +1,161 @@
+use {
+    syn::{LitStr, LitInt, Ident, Token, token, parenthesized},
+    syn::parse::{ParseStream, Result}
+};
+
+// version(1, 2) [deprecated([1, ..,] since = "..." [, sunset = "..."])] { .. } inside a scope,
+// both dates being HTTP dates like "Sun, 01 Jun 2025 00:00:00 GMT"
+pub struct Versions {
+    pub numbers: Vec<u32>,
+    deprecated: Vec<u32>,
+    // seconds since the epoch, for the `Deprecation: @1748736000` header of RFC 9745
+    since: Option<i64>,
+    sunset: Option<LitStr>
+}
+
+impl Versions {
+    pub fn parse(args: ParseStream, keyword: &Ident) -> Result<Self> {
+        let mut numbers: Vec<u32> = Vec::new();
+
+        while !args.is_empty() {
+            let number: LitInt = args.parse()?;
+            numbers.push( number.base10_parse()? );
+
+            if !args.is_empty() {
+                let _comma: Token![,] = args.parse()?;
+            }
+        }
+
+        if numbers.is_empty() {
+            return Err( syn::Error::new(keyword.span(), "version(..) needs at least one version number") )
+        }
+
+        Ok( Versions { numbers, deprecated: Vec::new(), since: None, sunset: None } )
+    }
+
+    // optional deprecated(..) clause, without version numbers every version of the block is deprecated
+    pub fn parse_deprecation(&mut self, stream: ParseStream) -> Result<()> {
+        let fork = stream.fork();
+        match fork.parse::<Ident>() {
+            Ok(ident) if ident == "deprecated" => {},
+            _ => return Ok(())
+        }
+
+        let keyword: Ident = stream.parse()?;
+        let mut deprecated: Vec<u32> = Vec::new();
+
+        if stream.peek(token::Paren) {
+            let args;
+            parenthesized!( args in stream );
+
+            while !args.is_empty() {
+                if args.peek(LitInt) {
+                    let number: LitInt = args.parse()?;
+                    let version: u32 = number.base10_parse()?;
+                    if !self.numbers.contains(&version) {
+                        return Err( syn::Error::new(number.span(), format!("Version {} is not served by this block", version)) )
+                    }
+                    deprecated.push(version);
+                } else {
+                    let key: Ident = args.parse()?;
+                    let _eq: Token![=] = args.parse()?;
+
+                    if key == "since" {
+                        let since: LitStr = args.parse()?;
+                        match unix_seconds(&since.value()) {
+                            Some(seconds) => self.since = Some(seconds),
+                            None => return Err( syn::Error::new(since.span(), "Invalid deprecation date, expected an HTTP date like \"Sun, 01 Jun 2025 00:00:00 GMT\"") )
+                        }
+                    } else if key == "sunset" {
+                        self.sunset = Some(args.parse()?);
+                    } else {
+                        return Err( syn::Error::new(key.span(), format!("Unknown deprecated option `{}`, expected `since` or `sunset`", key)) )
+                    }
+                }
+
+                if !args.is_empty() {
+                    let _comma: Token![,] = args.parse()?;
+                }
+            }
+        }
+
+        if self.since.is_none() {
+            return Err( syn::Error::new(keyword.span(), "deprecated(..) needs the date of the deprecation, e.g. since = \"Sun, 01 Jun 2025 00:00:00 GMT\"") )
+        }
+
+        self.deprecated = if deprecated.is_empty() { self.numbers.clone() } else { deprecated };
+        Ok(())
+    }
+
+    // `default` versions also answer requests that do not ask for a version
+    pub fn guard(&self, version: u32, default: bool) -> proc_macro2::TokenStream {
+        quote!{ roolz::route::versioning::version( #version, #default ) }
+    }
+
+    pub fn headers(&self, version: u32) -> Option<proc_macro2::TokenStream> {
+        let since = match self.since {
+            Some(since) if self.deprecated.contains(&version) => since,
+            _ => return None
+        };
+
+        Some(
+            match &self.sunset {
+                Some(sunset) => quote!{ roolz::route::versioning::deprecation( #since, Some( #sunset ) ) },
+                None => quote!{ roolz::route::versioning::deprecation( #since, None ) }
+            }
+        )
+    }
+}
+
+const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+// seconds since the epoch of an IMF-fixdate, e.g. "Sun, 01 Jun 2025 00:00:00 GMT"
+fn unix_seconds(date: &str) -> Option<i64> {
+    let parts: Vec<&str> = date.split_whitespace().collect();
+    let (day, month, year, time) = match parts.as_slice() {
+        [_weekday, day, month, year, time, "GMT"] => (day, month, year, time),
+        _ => return None
+    };
+
+    let day: i64 = day.parse().ok().filter(|day| (1..=31).contains(day))?;
+    let month = MONTHS.iter().position(|m| m == month)? as i64 + 1;
+    let year: i64 = year.parse().ok().filter(|year| *year >= 1970)?;
+
+    let clock: Vec<i64> = time.split(':').map(|n| n.parse().ok()).collect::<Option<Vec<i64>>>()?;
+    let seconds = match clock.as_slice() {
+        [h, m, s] if *h < 24 && *m < 60 && *s < 60 => h * 3600 + m * 60 + s,
+        _ => return None
+    };
+
+    // days from the civil date, counting years from March so leap days come last
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let year_of_era = y - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    Some( days * 86_400 + seconds )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_http_dates() {
+        assert_eq!(unix_seconds("Thu, 01 Jan 1970 00:00:00 GMT"), Some(0));
+        assert_eq!(unix_seconds("Sun, 01 Jun 2025 00:00:00 GMT"), Some(1_748_736_000));
+        assert_eq!(unix_seconds("Thu, 29 Feb 2024 12:30:15 GMT"), Some(1_709_209_815));
+        assert_eq!(unix_seconds("Fri, 31 Dec 1999 23:59:59 GMT"), Some(946_684_799));
+    }
+
+    #[test]
+    fn rejects_other_dates() {
+        assert_eq!(unix_seconds("2025-06-01"), None);
+        assert_eq!(unix_seconds("Sun, 01 Jun 2025 00:00:00 UTC"), None);
+        assert_eq!(unix_seconds("Sun, 32 Jun 2025 00:00:00 GMT"), None);
+        assert_eq!(unix_seconds("Sun, 01 June 2025 00:00:00 GMT"), None);
+        assert_eq!(unix_seconds("Sun, 01 Jun 2025 24:00:00 GMT"), None);
+    }
+}
@@ -7,7 +7,7 @@ use proc_macro2;
 use syn::spanned::Spanned;
 
 use syn::{
-     Path, Ident, Type, Attribute, Result,
+     Path, Ident, Type, Attribute, Result, AttributeArgs, Meta, NestedMeta,
     GenericArgument, PathArguments, Fields, Item, ItemStruct
 };
 
@@ -15,16 +15,26 @@ use proc_macro_error::{proc_macro_error, emit_error};
 
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn table_model(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn table_model(attr: TokenStream, item: TokenStream) -> TokenStream {
     let model: Item = syn::parse(item).expect("failed to parse model struct");
+    let options = parse_options( syn::parse_macro_input!(attr as AttributeArgs) );
 
     match model {
         Item::Struct(ref struct_model) => {
-            let table_model: proc_macro2::TokenStream = build_model(&struct_model, &model);
-            let proxy: proc_macro2::TokenStream = build_proxy(&struct_model, &model);
+            let key = match primary_key(struct_model, &options) {
+                Some(key) => key,
+                None => return TokenStream::new()
+            };
+
+            let table_model: proc_macro2::TokenStream = build_model(&struct_model, &model, &options);
+            let proxy: proc_macro2::TokenStream = build_proxy(&struct_model, &model, &options);
             let deps: proc_macro2::TokenStream = import_schema_dependencies(&struct_model.ident);
             let boxed_query: proc_macro2::TokenStream = build_boxed_query(&struct_model.ident);
-            let crud: proc_macro2::TokenStream = build_crud_methods(&struct_model.ident);
+            let crud: proc_macro2::TokenStream = build_crud_methods(&struct_model.ident, &key, &options);
+            let pagination: proc_macro2::TokenStream = build_pagination(&key);
+            let keyset_pagination: proc_macro2::TokenStream = build_keyset_pagination(struct_model, &key, &options);
+            let filter: proc_macro2::TokenStream = build_filter(struct_model, &model, &key);
+            let schemas: proc_macro2::TokenStream = build_schemas(struct_model);
 
             let expanded = quote! {
                 #deps
@@ -32,6 +42,10 @@ pub fn table_model(_attr: TokenStream, item: TokenStream) -> TokenStream {
                 #proxy
                 #boxed_query
                 #crud
+                #pagination
+                #keyset_pagination
+                #filter
+                #schemas
             };
 
             return TokenStream::from(expanded)
@@ -43,14 +57,140 @@ pub fn table_model(_attr: TokenStream, item: TokenStream) -> TokenStream {
     TokenStream::new()
 }
 
-fn build_model(model: &ItemStruct, parsed_model: &Item) -> proc_macro2::TokenStream {
+// arguments of the attribute, e.g. #[table_model(client_key)], #[table_model(primary_key(user_id, role_id))]
+// or #[table_model(cursor(created_at, id))]
+#[derive(Default)]
+struct Options {
+    // keys supplied by clients rather than generated by the database, kept by `create`
+    client_key: bool,
+    primary_key: Option<Vec<Ident>>,
+    // the columns keyset pagination orders by, the primary key unless given
+    cursor: Option<Vec<Ident>>
+}
+
+fn parse_options(args: AttributeArgs) -> Options {
+    let mut options = Options::default();
+
+    for arg in args {
+        match arg {
+            NestedMeta::Meta(Meta::Path(ref path)) if path.is_ident("client_key") => options.client_key = true,
+            NestedMeta::Meta(Meta::List(ref list)) if list.path.is_ident("primary_key") => {
+                options.primary_key = key_columns(list);
+                if options.primary_key.is_none() {
+                    emit_error!(list.span(), "Expected the primary key columns, e.g. primary_key(user_id, role_id)");
+                }
+            },
+            NestedMeta::Meta(Meta::List(ref list)) if list.path.is_ident("cursor") => {
+                options.cursor = key_columns(list);
+                if options.cursor.is_none() {
+                    emit_error!(list.span(), "Expected the columns to paginate by, e.g. cursor(created_at, id)");
+                }
+            },
+            _ => emit_error!(arg.span(), "Unknown table_model option, expected one of: client_key, primary_key(..), cursor(..)")
+        }
+    }
+
+    options
+}
+
+fn key_columns(list: &syn::MetaList) -> Option<Vec<Ident>> {
+    let columns: Vec<Ident> = list.nested.iter().filter_map(|column| match column {
+        NestedMeta::Meta(Meta::Path(path)) => path.get_ident().cloned(),
+        _ => None
+    }).collect();
+
+    if columns.is_empty() || columns.len() != list.nested.len() {
+        None
+    } else {
+        Some(columns)
+    }
+}
+
+// one field for most tables, several for composite keys
+struct PrimaryKey {
+    fields: Vec<Ident>,
+    types: Vec<Type>
+}
+
+impl PrimaryKey {
+    fn is_composite(&self) -> bool {
+        self.fields.len() > 1
+    }
+
+    // the type taken by find, delete and record_exists, a tuple for composite keys
+    fn typ(&self) -> proc_macro2::TokenStream {
+        let types = &self.types;
+        if self.is_composite() { quote!{ ( #( #types ),* ) } } else { quote!{ #( #types )* } }
+    }
+}
+
+// the columns given to #[table_model(primary_key(..))] or diesel's #[primary_key(..)], or else `id`
+fn primary_key(model: &ItemStruct, options: &Options) -> Option<PrimaryKey> {
+    let mut names = vec![ format_ident!("id") ];
+
+    for attribute in model.attrs.iter().filter(|a| a.path.is_ident("primary_key")) {
+        if options.primary_key.is_some() {
+            emit_error!(attribute.span(), "The primary key is already given to #[table_model(primary_key(..))]");
+            return None
+        }
+
+        names = match attribute.parse_meta() {
+            Ok(Meta::List(ref list)) => key_columns(list),
+            _ => None
+        }.unwrap_or_default();
+
+        if names.is_empty() {
+            emit_error!(attribute.span(), "Expected the primary key columns, e.g. #[primary_key(code)]");
+            return None
+        }
+    }
+
+    if let Some(columns) = &options.primary_key {
+        names = columns.clone();
+    }
+
+    let mut key = PrimaryKey { fields: Vec::new(), types: Vec::new() };
+
+    for name in names {
+        let field = match model.fields {
+            Fields::Named(ref fields) => fields.named.iter().find(|f| f.ident.as_ref() == Some(&name)),
+            _ => None
+        };
+
+        match field {
+            Some(field) => {
+                key.types.push(field.ty.clone());
+                key.fields.push(name);
+            },
+            None => {
+                emit_error!(model.ident.span(), "The struct has no `{}` field, name its primary key with #[table_model(primary_key(field))]", name);
+                return None
+            }
+        }
+    }
+
+    Some(key)
+}
+
+// diesel's attribute for Identifiable, unless the struct carries it already
+fn key_attribute(options: &Options) -> proc_macro2::TokenStream {
+    match &options.primary_key {
+        Some(columns) => quote!{ #[primary_key( #( #columns ),* )] },
+        None => quote!{}
+    }
+}
+
+fn build_model(model: &ItemStruct, parsed_model: &Item, options: &Options) -> proc_macro2::TokenStream {
     let table = &model.ident.to_string();
     let attributes: &Vec<Attribute> = &model.attrs;
+    let key_attribute = key_attribute(options);
 
     let mut tokenized_fields: Vec<proc_macro2::TokenStream> = Vec::new();
     match model.fields {
         Fields::Named(ref fields) => {
             for field in &fields.named {
+                let mut field = field.clone();
+                field.attrs.retain(|attribute| !attribute.path.is_ident("filterable"));
                 tokenized_fields.push(quote!{ #field, } )
             }
 
@@ -61,6 +201,7 @@ fn build_model(model: &ItemStruct, parsed_model: &Item) -> proc_macro2::TokenStr
     quote!{
         #[derive(Identifiable, Eq, PartialEq, Debug, Queryable, QueryableByName, Serialize, AsChangeset)]
         #( #attributes )*
+        #key_attribute
         #[table_name=#table]
         pub struct Model {
             #( #tokenized_fields )*
@@ -68,9 +209,10 @@ fn build_model(model: &ItemStruct, parsed_model: &Item) -> proc_macro2::TokenStr
     }
 }
 
-fn build_proxy(model: &ItemStruct, parsed_model: &Item) -> proc_macro2::TokenStream {
+fn build_proxy(model: &ItemStruct, parsed_model: &Item, options: &Options) -> proc_macro2::TokenStream {
     let table = &model.ident.to_string();
     let attributes: &Vec<Attribute> = &model.attrs;
+    let key_attribute = key_attribute(options);
 
     let mut tokenized_fields: Vec<proc_macro2::TokenStream> = Vec::new();
 
@@ -92,6 +234,7 @@ fn build_proxy(model: &ItemStruct, parsed_model: &Item) -> proc_macro2::TokenStr
     quote!{
         #[derive(Identifiable, Insertable, Deserialize, AsChangeset, Debug, Clone)]
         #( #attributes )*
+        #key_attribute
         #[table_name=#table]
         pub struct Proxy {
             #( #tokenized_fields )*
@@ -114,11 +257,27 @@ fn build_boxed_query(table: &Ident) -> proc_macro2::TokenStream {
     }
 }
 
-fn build_crud_methods(table: &Ident) -> proc_macro2::TokenStream {
+fn build_crud_methods(table: &Ident, key: &PrimaryKey, options: &Options) -> proc_macro2::TokenStream {
+    let fields = &key.fields;
+    let typ = key.typ();
+
+    // left for the database to generate; composite keys are always supplied by the client
+    let reset_key = if options.client_key || key.is_composite() {
+        quote!{}
+    } else {
+        quote!{ #( self.#fields = None; )* }
+    };
+
+    // bindings named apart from the columns imported from the schema
+    let bindings: Vec<Ident> = (0..fields.len()).map(|i| format_ident!("pkey_{}", i)).collect();
+    let pkey = if key.is_composite() { quote!{ ( #( #bindings ),* ) } } else { quote!{ #( #bindings )* } };
+
     quote! {
+        pub type PrimaryKey = #typ;
+
         impl Proxy {
             pub fn create(&mut self) -> AppResult<Model> {
-                self.id = None;
+                #reset_key
                 match diesel::insert_into(#table).values(&*self).get_result(&db()) {
                     Ok(record) => Ok(record),
                     Err(e) => Err( DBError::for_app(e) )
@@ -126,9 +285,10 @@ fn build_crud_methods(table: &Ident) -> proc_macro2::TokenStream {
             }
 
             pub fn update(&mut self) -> AppResult<Model> {
-                if let Some(i_d) = self.id {
-                    if record_exists(i_d) {
-                        match diesel::update(#table.find(i_d)).set(&*self).get_result(&db()) {
+                if let ( #( Some(#bindings), )* ) = ( #( self.#fields.clone(), )* ) {
+                    let pkey: PrimaryKey = #pkey;
+                    if record_exists(pkey.clone()) {
+                        match diesel::update(#table.find(pkey)).set(&*self).get_result(&db()) {
                             Ok(record) => Ok(record),
                             Err(e) => Err( DBError::for_app(e) )
                         }
@@ -141,15 +301,15 @@ fn build_crud_methods(table: &Ident) -> proc_macro2::TokenStream {
             }
         }
 
-        pub fn find(pkey: i32) -> AppResult<Model> {
+        pub fn find(pkey: PrimaryKey) -> AppResult<Model> {
             match #table.find(pkey).get_result(&db()) {
                 Ok(model) => Ok(model),
                 Err(_e) => Err( not_found("No record exists for this ID") )
             }
         }
 
-        pub fn delete(pkey: i32) -> AppResult<Model> {
-            if record_exists(pkey) {
+        pub fn delete(pkey: PrimaryKey) -> AppResult<Model> {
+            if record_exists(pkey.clone()) {
                 match diesel::delete(#table.find(pkey)).get_result(&db()) {
                     Ok(model) => Ok(model),
                     Err(_e) => Err( not_found("No record exists for this ID") )
@@ -160,7 +320,7 @@ fn build_crud_methods(table: &Ident) -> proc_macro2::TokenStream {
 
         }
 
-        pub fn record_exists(pkey: i32) -> bool {
+        pub fn record_exists(pkey: PrimaryKey) -> bool {
             let query = #table.find(pkey);
 
             match select(exists(query)).get_result(&db()) {
@@ -170,6 +330,293 @@ fn build_crud_methods(table: &Ident) -> proc_macro2::TokenStream {
     }
 }
 
+// offset pagination over boxed_query(), ordered by the primary key so pages are stable; bindings are
+// named apart from the columns imported from the schema, which may well include `total` or `page`
+fn build_pagination(key: &PrimaryKey) -> proc_macro2::TokenStream {
+    let fields = &key.fields;
+    let order = if key.is_composite() { quote!{ ( #( #fields ),* ) } } else { quote!{ #( #fields )* } };
+
+    quote! {
+        pub fn paginate(__page: i64, __per_page: i64) -> AppResult<roolz::model::pagination::Page<Model>> {
+            let (__page, __per_page) = roolz::model::pagination::bounds(__page, __per_page);
+            let __offset = roolz::model::pagination::offset(__page, __per_page)?;
+
+            let __total: i64 = match boxed_query().count().get_result(&db()) {
+                Ok(__total) => __total,
+                Err(e) => return Err( DBError::for_app(e) )
+            };
+
+            match boxed_query().order(#order).limit(__per_page).offset(__offset).load(&db()) {
+                Ok(__items) => Ok( roolz::model::pagination::Page::new(__items, __total, __page, __per_page) ),
+                Err(e) => Err( DBError::for_app(e) )
+            }
+        }
+    }
+}
+
+// keyset pagination: the sort tuple of the last record becomes a signed cursor, and the next page
+// holds the records sorting after it, e.g. for cursor(created_at, id)
+// created_at > c0 OR (created_at = c0 AND id > c1); bindings are named apart from the columns, as in paginate
+fn build_keyset_pagination(model: &ItemStruct, key: &PrimaryKey, options: &Options) -> proc_macro2::TokenStream {
+    let table = model.ident.to_string();
+    let columns = options.cursor.clone().unwrap_or_else(|| key.fields.clone());
+
+    let mut types: Vec<Type> = Vec::new();
+    for column in &columns {
+        let field = match model.fields {
+            Fields::Named(ref fields) => fields.named.iter().find(|f| f.ident.as_ref() == Some(column)),
+            _ => None
+        };
+
+        match field {
+            Some(field) if is_option(&field.ty) => {
+                emit_error!(column.span(), "Cannot paginate by the nullable column `{}`", column);
+                return quote!{}
+            },
+            Some(field) => types.push(field.ty.clone()),
+            None => {
+                emit_error!(column.span(), "The struct has no `{}` field to paginate by", column);
+                return quote!{}
+            }
+        }
+    }
+
+    let values: Vec<Ident> = (0..columns.len()).map(|i| format_ident!("cursor_{}", i)).collect();
+
+    let mut after = quote!{};
+    for (i, (column, value)) in columns.iter().zip(values.iter()).enumerate().rev() {
+        after = if i == columns.len() - 1 {
+            quote!{ #column.gt(#value.clone()) }
+        } else {
+            quote!{ #column.gt(#value.clone()).or( #column.eq(#value.clone()).and( #after ) ) }
+        };
+    }
+
+    let order = if columns.len() > 1 { quote!{ ( #( #columns.asc() ),* ) } } else { quote!{ #( #columns.asc() )* } };
+
+    quote! {
+        pub fn paginate_after(__after: Option<&str>, __per_page: i64) -> AppResult<roolz::model::pagination::CursorPage<Model>> {
+            let (_, __per_page) = roolz::model::pagination::bounds(1, __per_page);
+            // one more than asked for tells whether a next page exists
+            let mut __query = boxed_query().order(#order).limit(__per_page + 1);
+
+            if let Some(__after) = __after {
+                let ( #( #values, )* ): ( #( #types, )* ) = roolz::model::pagination::cursor::decode(#table, __after)?;
+                __query = __query.filter( #after );
+            }
+
+            let mut __items: Vec<Model> = match __query.load(&db()) {
+                Ok(__items) => __items,
+                Err(e) => return Err( DBError::for_app(e) )
+            };
+
+            let __next = if __items.len() as i64 > __per_page {
+                __items.truncate(__per_page as usize);
+                __items.last().map(|__last| roolz::model::pagination::cursor::encode(#table, &( #( __last.#columns.clone(), )* ))).transpose()?
+            } else {
+                None
+            };
+
+            Ok( roolz::model::pagination::CursorPage::new(__items, __per_page, __next) )
+        }
+    }
+}
+
+// a `Filter` with operators for each #[filterable] field, e.g. ?title_like=%rust%&id_in=1,2&body_is_null=false,
+// all of them applied to boxed_query() by where_filter, with bindings named apart from the columns
+fn build_filter(model: &ItemStruct, parsed_model: &Item, key: &PrimaryKey) -> proc_macro2::TokenStream {
+    let mut fields: Vec<proc_macro2::TokenStream> = Vec::new();
+    let mut filters: Vec<proc_macro2::TokenStream> = Vec::new();
+    let mut properties: Vec<proc_macro2::TokenStream> = Vec::new();
+
+    let filterable = match model.fields {
+        Fields::Named(ref fields) => fields.named.iter().filter(|f| f.attrs.iter().any(|a| a.path.is_ident("filterable"))).collect(),
+        _ => Vec::new()
+    };
+
+    for field in filterable {
+        let column = match &field.ident {
+            Some(column) => column,
+            None => continue
+        };
+
+        let typ = match extract_type_from_option(&field.ty, parsed_model) {
+            Ok(typ) => typ,
+            Err(_) => continue
+        };
+        let schema = type_schema(typ);
+
+        for (operator, method) in [("eq", "eq"), ("ne", "ne"), ("lt", "lt"), ("gt", "gt")].iter() {
+            let name = format_ident!("{}_{}", column, operator);
+            let method = format_ident!("{}", method);
+            let property = name.to_string();
+
+            fields.push( quote!{ pub #name: Option<#typ>, } );
+            filters.push( quote!{ if let Some(__value) = __filter.#name { __query = __query.filter( #column.#method(__value) ); } } );
+            properties.push( quote!{ (#property, #schema) } );
+        }
+
+        let name = format_ident!("{}_in", column);
+        let property = name.to_string();
+        fields.push( quote!{ #[serde(default, deserialize_with = "roolz::model::filter::list")] pub #name: Option<Vec<#typ>>, } );
+        filters.push( quote!{ if let Some(__values) = __filter.#name { __query = __query.filter( #column.eq_any(__values) ); } } );
+        properties.push( quote!{ (#property, roolz::route::openapi::typed("string", None)) } );
+
+        if is_string(typ) {
+            let name = format_ident!("{}_like", column);
+            let property = name.to_string();
+            fields.push( quote!{ pub #name: Option<String>, } );
+            filters.push( quote!{ if let Some(__pattern) = __filter.#name { __query = __query.filter( #column.like(__pattern) ); } } );
+            properties.push( quote!{ (#property, roolz::route::openapi::typed("string", None)) } );
+        }
+
+        if is_option(&field.ty) {
+            let name = format_ident!("{}_is_null", column);
+            let property = name.to_string();
+            fields.push( quote!{ pub #name: Option<bool>, } );
+            filters.push( quote!{
+                match __filter.#name {
+                    Some(true) => __query = __query.filter( #column.is_null() ),
+                    Some(false) => __query = __query.filter( #column.is_not_null() ),
+                    None => {}
+                }
+            } );
+            properties.push( quote!{ (#property, roolz::route::openapi::typed("boolean", None)) } );
+        }
+    }
+
+    if fields.is_empty() {
+        return quote!{}
+    }
+
+    let key_fields = &key.fields;
+    let order = if key.is_composite() { quote!{ ( #( #key_fields ),* ) } } else { quote!{ #( #key_fields )* } };
+
+    quote! {
+        #[derive(Deserialize, Default, Debug, Clone)]
+        pub struct Filter {
+            #( #fields )*
+        }
+
+        impl roolz::route::openapi::Schema for Filter {
+            fn schema() -> roolz::route::openapi::Value {
+                roolz::route::openapi::object(vec![ #( #properties ),* ], &[])
+            }
+        }
+
+        pub fn where_filter(__filter: Filter) -> AppResult<Vec<Model>> {
+            let mut __query = boxed_query();
+            #( #filters )*
+
+            match __query.order(#order).load(&db()) {
+                Ok(__records) => Ok(__records),
+                Err(e) => Err( DBError::for_app(e) )
+            }
+        }
+    }
+}
+
+// OpenAPI schemas for the Model and Proxy, so both can be documented as request and response bodies
+fn build_schemas(model: &ItemStruct) -> proc_macro2::TokenStream {
+    let mut model_properties: Vec<proc_macro2::TokenStream> = Vec::new();
+    let mut proxy_properties: Vec<proc_macro2::TokenStream> = Vec::new();
+    let mut required: Vec<String> = Vec::new();
+
+    if let Fields::Named(ref fields) = model.fields {
+        for field in &fields.named {
+            let name = field.ident.as_ref().map(|i| i.to_string()).unwrap_or_default();
+            let schema = type_schema(&field.ty);
+
+            if is_option(&field.ty) {
+                proxy_properties.push( quote!{ (#name, #schema) } );
+            } else {
+                required.push(name.clone());
+                proxy_properties.push( quote!{ (#name, roolz::route::openapi::nullable(#schema)) } );
+            }
+
+            model_properties.push( quote!{ (#name, #schema) } );
+        }
+    }
+
+    quote!{
+        impl roolz::route::openapi::Schema for Model {
+            fn schema() -> roolz::route::openapi::Value {
+                roolz::route::openapi::object(vec![ #( #model_properties ),* ], &[ #( #required ),* ])
+            }
+        }
+
+        impl roolz::route::openapi::Schema for Proxy {
+            fn schema() -> roolz::route::openapi::Value {
+                roolz::route::openapi::object(vec![ #( #proxy_properties ),* ], &[])
+            }
+        }
+    }
+}
+
+fn type_schema(ty: &Type) -> proc_macro2::TokenStream {
+    let segment = match ty {
+        Type::Path(typepath) => typepath.path.segments.last(),
+        _ => None
+    };
+
+    let segment = match segment {
+        Some(segment) => segment,
+        None => return quote!{ roolz::route::openapi::Value::Object(Default::default()) }
+    };
+
+    let inner = match &segment.arguments {
+        PathArguments::AngleBracketed(params) => match params.args.first() {
+            Some(GenericArgument::Type(inner)) => Some(inner),
+            _ => None
+        },
+        _ => None
+    };
+
+    let (kind, format) = match segment.ident.to_string().as_str() {
+        "Option" | "Vec" if inner.is_some() => {
+            let inner = type_schema(inner.unwrap());
+            return if segment.ident == "Option" {
+                quote!{ roolz::route::openapi::nullable(#inner) }
+            } else {
+                quote!{ roolz::route::openapi::array(#inner) }
+            }
+        },
+        "i8" | "i16" | "i32" | "u8" | "u16" => ("integer", Some("int32")),
+        "i64" | "u32" | "u64" => ("integer", Some("int64")),
+        "f32" => ("number", Some("float")),
+        "f64" => ("number", Some("double")),
+        "BigDecimal" => ("string", Some("decimal")),
+        "bool" => ("boolean", None),
+        "String" | "str" => ("string", None),
+        "NaiveDateTime" | "DateTime" | "SystemTime" => ("string", Some("date-time")),
+        "NaiveDate" => ("string", Some("date")),
+        "NaiveTime" => ("string", Some("time")),
+        "Uuid" => ("string", Some("uuid")),
+        _ => return quote!{ roolz::route::openapi::Value::Object(Default::default()) }
+    };
+
+    let format = match format {
+        Some(format) => quote!{ Some(#format) },
+        None => quote!{ None }
+    };
+
+    quote!{ roolz::route::openapi::typed(#kind, #format) }
+}
+
+fn is_string(ty: &Type) -> bool {
+    match ty {
+        Type::Path(typepath) => typepath.path.segments.last().map(|s| s.ident == "String").unwrap_or(false),
+        _ => false
+    }
+}
+
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(typepath) => typepath.path.segments.last().map(|s| s.ident == "Option").unwrap_or(false),
+        _ => false
+    }
+}
+
 
 // THIS HEINOUS SHIT MAKES ME WANNA HAVE THE TROTS
 
@@ -6,6 +6,7 @@ pub use {
 
 
 #[macro_export]
+#[allow(clippy::crate_in_macro_def)]
 macro_rules! controllers {
     ( $( $controller:ident )* ) => {
         pub use roolz::controller::*;
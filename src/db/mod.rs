@@ -17,7 +17,7 @@ pub use {
 
 pub fn db() -> DBPooledConnection {
     HANDLER.db_connection.get().
-        expect(&format!("Error connecting to {}", db_url()))
+        unwrap_or_else(|_| panic!("Error connecting to {}", db_url()))
 }
 
 type DBPool = Pool<ConnectionManager<PgConnection>>;
@@ -43,7 +43,7 @@ fn db_url() -> String {
     env::var("DATABASE_URL").expect("DATABASE_URL must be set")
 }
 
-fn db_pool() -> u32 { env::var("DB_POOL").expect("DB_POOL must be set").parse::<u32>().ok().expect("DB_POOL must be an unsigned integer") }
+fn db_pool() -> u32 { env::var("DB_POOL").expect("DB_POOL must be set").parse::<u32>().expect("DB_POOL must be an unsigned integer") }
 
 pub type DBResult<T> = std::result::Result<T, DBError>;
 
@@ -56,7 +56,7 @@ pub enum DBError {
 
 impl ErrorMeta for DBError {
     fn code(&self) -> Option<StatusCode> {
-        match &*self {
+        match self {
             DBError::Conflict(_) => Some(StatusCode::CONFLICT),
             DBError::Connection(_) => Some(StatusCode::SERVICE_UNAVAILABLE),
             DBError::App(e) => e.code()
@@ -68,7 +68,7 @@ impl ErrorMeta for DBError {
     }
 
     fn kind(&self) -> String {
-        match &*self {
+        match self {
             DBError::Conflict(_) => String::from("Conflict"),
             DBError::Connection(_) => String::from("Connection"),
             DBError::App(e) => e.kind()
@@ -76,7 +76,7 @@ impl ErrorMeta for DBError {
     }
 
     fn origin(&self) -> String {
-        match &*self {
+        match self {
             DBError::Conflict(_) => String::from("diesel::result::Error"),
             DBError::Connection(_) => String::from("diesel::result::ConnectionError"),
             DBError::App(e) => e.origin()
@@ -106,7 +106,7 @@ impl std::error::Error for DBError {
 
 impl std::fmt::Display for DBError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        match &*self {
+        match self {
             DBError::Conflict( e ) => e.fmt(f),
             DBError::Connection( e ) => e.fmt(f),
             DBError::App( e ) => e.fmt(f)
@@ -52,35 +52,35 @@ impl ForApp<AppError> for AppError {
 
 impl ErrorMeta for AppError {
     fn code(&self) -> Option<StatusCode> {
-        match &*self {
+        match self {
             AppError::New(_, c) => *c,
             AppError::From(e) => e.code()
         }
     }
 
     fn typ(&self) -> String {
-        match &*self {
+        match self {
             AppError::New(_, _) => String::from("AppError"),
             AppError::From(e) => e.typ()
         }
     }
 
     fn kind(&self) -> String {
-        match &*self {
+        match self {
             AppError::New(_, _) => String::from("New"),
             AppError::From(e) => e.kind()
         }
     }
 
     fn origin(&self) -> String {
-        match &*self {
+        match self {
             AppError::New(_, _) => String::new(),
             AppError::From(e) => e.origin()
         }
     }
 
     fn reason(&self) -> String {
-        match &*self {
+        match self {
             AppError::New(m, _) => m.to_string(),
             AppError::From(e) => {
                 if let Some(source) = e.source() {
@@ -116,10 +116,18 @@ pub mod helpers {
     use actix_web::http::StatusCode;
     use super::AppError;
 
+    pub fn bad_request(message: &'static str) -> AppError {
+        AppError::New(message, Some(StatusCode::BAD_REQUEST))
+    }
+
     pub fn not_found(message: &'static str) -> AppError {
         AppError::New(message, Some(StatusCode::NOT_FOUND))
     }
 
+    pub fn method_not_allowed(message: &'static str) -> AppError {
+        AppError::New(message, Some(StatusCode::METHOD_NOT_ALLOWED))
+    }
+
     pub fn conflict(message: &'static str) -> AppError {
         AppError::New(message, Some(StatusCode::CONFLICT))
     }
@@ -128,10 +136,18 @@ pub mod helpers {
         AppError::New(message, Some(StatusCode::UNPROCESSABLE_ENTITY))
     }
 
+    pub fn too_many_requests(message: &'static str) -> AppError {
+        AppError::New(message, Some(StatusCode::TOO_MANY_REQUESTS))
+    }
+
     pub fn service_unavailable(message: &'static str) -> AppError {
         AppError::New(message, Some(StatusCode::SERVICE_UNAVAILABLE))
     }
 
+    pub fn gateway_timeout(message: &'static str) -> AppError {
+        AppError::New(message, Some(StatusCode::GATEWAY_TIMEOUT))
+    }
+
     pub fn internal_server_error(message: &'static str) -> AppError {
         AppError::New(message, Some(StatusCode::INTERNAL_SERVER_ERROR))
     }
@@ -17,4 +17,5 @@ pub mod model;
 pub mod error;
 pub mod route;
 pub mod view;
-pub mod controller;
\ No newline at end of file
+pub mod controller;
+pub mod test;
\ No newline at end of file
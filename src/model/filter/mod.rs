@@ -0,0 +1,23 @@
+use {
+    serde::{ de::Error, Deserialize, Deserializer },
+    std::{ fmt::Display, str::FromStr }
+};
+
+// the values of `_in` filters, given comma separated as query strings cannot hold lists,
+// e.g. ?id_in=1,2,3
+pub fn list<'de, D, T>(deserializer: D) -> Result<Option<Vec<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display
+{
+    let values: Option<String> = Option::deserialize(deserializer)?;
+
+    match values {
+        Some(values) => values.split(',')
+            .map(|value| value.trim().parse::<T>().map_err(D::Error::custom))
+            .collect::<Result<Vec<T>, D::Error>>()
+            .map(Some),
+        None => Ok(None)
+    }
+}
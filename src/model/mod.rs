@@ -3,6 +3,8 @@ pub use {
     crate::error::{AppError, AppResult, register_errors, ForApp, helpers::*}
 };
 
+pub mod filter;
+pub mod pagination;
 pub mod resource_model;
 
 #[cfg(feature="database")]
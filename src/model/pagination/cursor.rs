@@ -0,0 +1,117 @@
+use {
+    base64::{ engine::general_purpose::URL_SAFE_NO_PAD, Engine },
+    hmac::{ Hmac, Mac },
+    serde::{ de::DeserializeOwned, Serialize },
+    sha2::Sha256,
+    std::env,
+    crate::error::{ AppResult, helpers::{ bad_request, internal_server_error } }
+};
+
+type Signer = Hmac<Sha256>;
+
+pub const MIN_SECRET_LENGTH: usize = 32;
+
+lazy_static::lazy_static! {
+    // checked once; without a usable secret, requests paginating by cursor fail with a 500
+    static ref CURSOR_SECRET: Option<Vec<u8>> = valid_secret(env::var("CURSOR_SECRET").ok());
+}
+
+fn valid_secret(secret: Option<String>) -> Option<Vec<u8>> {
+    secret.filter(|secret| secret.len() >= MIN_SECRET_LENGTH).map(String::into_bytes)
+}
+
+// signs the payload along with what it paginates, so cursors of one table are refused by another
+fn signer(scope: &str, payload: &[u8]) -> AppResult<Signer> {
+    let secret = CURSOR_SECRET.as_ref().ok_or_else(|| internal_server_error("CURSOR_SECRET must be set to at least 32 bytes"))?;
+
+    let mut signer = Signer::new_from_slice(secret).map_err(|_| internal_server_error("Invalid CURSOR_SECRET"))?;
+    signer.update(scope.as_bytes());
+    signer.update(b"|");
+    signer.update(payload);
+    Ok(signer)
+}
+
+// an opaque `payload.signature` token, both base64url encoded
+pub fn encode<T: Serialize>(scope: &str, key: &T) -> AppResult<String> {
+    let payload = serde_json::to_vec(key).map_err(|_| internal_server_error("Unserializable pagination cursor"))?;
+    let signature = signer(scope, &payload)?.finalize().into_bytes();
+
+    Ok( format!("{}.{}", URL_SAFE_NO_PAD.encode(&payload), URL_SAFE_NO_PAD.encode(signature)) )
+}
+
+// forged, truncated or foreign cursors are a 400 rather than a database error
+pub fn decode<T: DeserializeOwned>(scope: &str, token: &str) -> AppResult<T> {
+    let invalid = || bad_request("Invalid pagination cursor");
+
+    let (payload, signature) = token.split_once('.').ok_or_else(invalid)?;
+    let payload = URL_SAFE_NO_PAD.decode(payload).map_err(|_| invalid())?;
+    let signature = URL_SAFE_NO_PAD.decode(signature).map_err(|_| invalid())?;
+
+    signer(scope, &payload)?.verify_slice(&signature).map_err(|_| invalid())?;
+    serde_json::from_slice(&payload).map_err(|_| invalid())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::error::ErrorMeta
+    };
+
+    fn status<T>(result: AppResult<T>) -> u16 {
+        result.err().and_then(|e| e.code()).map(|code| code.as_u16()).unwrap_or(0)
+    }
+
+    fn setup() {
+        env::set_var("CURSOR_SECRET", "a test secret long enough to sign with");
+    }
+
+    #[test]
+    fn round_trips() {
+        setup();
+        let token = encode("posts", &("2026-01-01T00:00:00", 5)).unwrap();
+        let key: (String, i32) = decode("posts", &token).unwrap();
+        assert_eq!(key, (String::from("2026-01-01T00:00:00"), 5));
+    }
+
+    #[test]
+    fn rejects_forged_tokens() {
+        setup();
+        let token = encode("posts", &(5,)).unwrap();
+        let (payload, signature) = token.split_once('.').unwrap();
+
+        // a payload re-encoded without the secret
+        let forged = format!("{}.{}", URL_SAFE_NO_PAD.encode(b"[6]"), signature);
+        assert_eq!(status(decode::<(i32,)>("posts", &forged)), 400);
+
+        let mut flipped = signature.to_string();
+        flipped.replace_range(0..1, if flipped.starts_with('A') { "B" } else { "A" });
+        assert_eq!(status(decode::<(i32,)>("posts", &format!("{}.{}", payload, flipped))), 400);
+    }
+
+    #[test]
+    fn rejects_truncated_tokens() {
+        setup();
+        let token = encode("posts", &(5,)).unwrap();
+        assert_eq!(status(decode::<(i32,)>("posts", &token[..token.len() - 2])), 400);
+        assert_eq!(status(decode::<(i32,)>("posts", token.split('.').next().unwrap())), 400);
+        assert_eq!(status(decode::<(i32,)>("posts", "")), 400);
+    }
+
+    #[test]
+    fn rejects_foreign_scopes() {
+        setup();
+        let token = encode("users", &(5,)).unwrap();
+        assert_eq!(status(decode::<(i32,)>("posts", &token)), 400);
+        // and keys of another shape
+        assert_eq!(status(decode::<(String, i32)>("users", &token)), 400);
+    }
+
+    #[test]
+    fn requires_a_long_secret() {
+        assert!(valid_secret(None).is_none());
+        assert!(valid_secret(Some(String::new())).is_none());
+        assert!(valid_secret(Some(String::from("short"))).is_none());
+        assert!(valid_secret(Some("x".repeat(MIN_SECRET_LENGTH))).is_some());
+    }
+}
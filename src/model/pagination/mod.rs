@@ -0,0 +1,195 @@
+use {
+    serde::{ Deserialize, Serialize, Serializer },
+    serde_json::json,
+    std::env,
+    crate::{
+        error::{ AppResult, helpers::bad_request },
+        route::openapi::{ self, Schema, Value }
+    }
+};
+
+pub mod cursor;
+
+pub const DEFAULT_PER_PAGE: i64 = 25;
+
+lazy_static::lazy_static! {
+    // the largest page clients may ask for, MAX_PER_PAGE when it is a positive integer or 100
+    static ref MAX_PER_PAGE: i64 = env::var("MAX_PER_PAGE").ok()
+        .and_then(|max| max.parse::<i64>().ok())
+        .filter(|max| *max > 0)
+        .unwrap_or(100);
+}
+
+pub fn max_per_page() -> i64 {
+    *MAX_PER_PAGE
+}
+
+// pages start at 1 and hold between 1 and max_per_page() items
+pub fn bounds(page: i64, per_page: i64) -> (i64, i64) {
+    (page.max(1), per_page.clamp(1, max_per_page()))
+}
+
+// rows skipped before a page; pages too far out for an i64 offset are refused
+pub fn offset(page: i64, per_page: i64) -> AppResult<i64> {
+    (page - 1).checked_mul(per_page).ok_or_else(|| bad_request("Invalid page"))
+}
+
+// ?page=2&per_page=50, or ?after=<cursor> for keyset pagination, for handlers to extract
+// with web::Query<PageParams>
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PageParams {
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+    pub after: Option<String>
+}
+
+impl PageParams {
+    pub fn page(&self) -> i64 {
+        self.page.unwrap_or(1)
+    }
+
+    pub fn per_page(&self) -> i64 {
+        self.per_page.unwrap_or(DEFAULT_PER_PAGE)
+    }
+
+    pub fn after(&self) -> Option<&str> {
+        self.after.as_deref()
+    }
+}
+
+impl Schema for PageParams {
+    fn schema() -> Value {
+        openapi::object(vec![
+            ("page", openapi::typed("integer", Some("int64"))),
+            ("per_page", openapi::typed("integer", Some("int64"))),
+            ("after", openapi::typed("string", None))
+        ], &[])
+    }
+}
+
+// one page of records along with the total across all pages
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64
+}
+
+impl<T> Page<T> {
+    pub fn new(items: Vec<T>, total: i64, page: i64, per_page: i64) -> Self {
+        Page { items, total, page, per_page }
+    }
+
+    // at least 1, so empty tables still have a first and last page
+    pub fn total_pages(&self) -> i64 {
+        ((self.total + self.per_page - 1) / self.per_page.max(1)).max(1)
+    }
+
+    pub fn has_prev(&self) -> bool {
+        self.page > 1
+    }
+
+    pub fn has_next(&self) -> bool {
+        self.page < self.total_pages()
+    }
+
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            total: self.total,
+            page: self.page,
+            per_page: self.per_page
+        }
+    }
+}
+
+// serialized as { "items": [..], "meta": { "page", "per_page", "total", "total_pages" } }
+impl<T: Serialize> Serialize for Page<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        json!(
+            {
+                "items": self.items,
+                "meta": {
+                    "page": self.page,
+                    "per_page": self.per_page,
+                    "total": self.total,
+                    "total_pages": self.total_pages()
+                }
+            }
+        ).serialize(serializer)
+    }
+}
+
+impl<T: Schema> Schema for Page<T> {
+    fn schema() -> Value {
+        let integer = || openapi::typed("integer", Some("int64"));
+        let meta = openapi::object(
+            vec![ ("page", integer()), ("per_page", integer()), ("total", integer()), ("total_pages", integer()) ],
+            &["page", "per_page", "total", "total_pages"]
+        );
+
+        openapi::object(vec![ ("items", openapi::array(T::schema())), ("meta", meta) ], &["items", "meta"])
+    }
+}
+
+// records following a cursor, with the cursor of the next page unless this is the last
+#[derive(Debug, Clone, PartialEq)]
+pub struct CursorPage<T> {
+    pub items: Vec<T>,
+    pub per_page: i64,
+    pub next: Option<String>
+}
+
+impl<T> CursorPage<T> {
+    pub fn new(items: Vec<T>, per_page: i64, next: Option<String>) -> Self {
+        CursorPage { items, per_page, next }
+    }
+
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> CursorPage<U> {
+        CursorPage {
+            items: self.items.into_iter().map(f).collect(),
+            per_page: self.per_page,
+            next: self.next
+        }
+    }
+}
+
+// serialized as { "items": [..], "meta": { "per_page", "next" } }
+impl<T: Serialize> Serialize for CursorPage<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        json!(
+            {
+                "items": self.items,
+                "meta": {
+                    "per_page": self.per_page,
+                    "next": self.next
+                }
+            }
+        ).serialize(serializer)
+    }
+}
+
+impl<T: Schema> Schema for CursorPage<T> {
+    fn schema() -> Value {
+        let meta = openapi::object(
+            vec![ ("per_page", openapi::typed("integer", Some("int64"))), ("next", openapi::nullable(openapi::typed("string", None))) ],
+            &["per_page", "next"]
+        );
+
+        openapi::object(vec![ ("items", openapi::array(T::schema())), ("meta", meta) ], &["items", "meta"])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offsets_pages() {
+        assert_eq!(offset(1, 25).ok(), Some(0));
+        assert_eq!(offset(3, 25).ok(), Some(50));
+        // e.g. ?page=9223372036854775807&per_page=100
+        assert!(offset(i64::MAX, 100).is_err());
+    }
+}
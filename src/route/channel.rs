@@ -0,0 +1,102 @@
+use {
+    actix_web::{ web, rt, HttpRequest, HttpResponse },
+    actix_ws::{ AggregatedMessage, AggregatedMessageStream, Session },
+    serde::{ Serialize, de::DeserializeOwned },
+    serde_json::json,
+    std::time::{ Duration, Instant }
+};
+
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(10);
+
+// a realtime endpoint declared with `ws "/path" @ Type` in `routes!`; messages in
+// both directions are JSON, one value per text frame
+pub trait Channel: Default + 'static {
+    type Message: DeserializeOwned;
+
+    fn on_connect(&mut self, _socket: &mut Socket, _req: &HttpRequest) {}
+    fn on_message(&mut self, socket: &mut Socket, message: Self::Message);
+    fn on_close(&mut self) {}
+}
+
+// outgoing side of a connection, flushed after every `Channel` callback
+#[derive(Default)]
+pub struct Socket {
+    outgoing: Vec<String>,
+    closing: bool
+}
+
+impl Socket {
+    pub fn send<T: Serialize>(&mut self, message: &T) {
+        match serde_json::to_string(message) {
+            Ok(text) => self.outgoing.push(text),
+            Err(e) => log::error!("Failed to serialize channel message: {}", e)
+        }
+    }
+
+    pub fn close(&mut self) {
+        self.closing = true;
+    }
+
+    // false once the connection should be closed
+    async fn flush(&mut self, session: &mut Session) -> bool {
+        for text in self.outgoing.drain(..) {
+            if session.text(text).await.is_err() {
+                return false
+            }
+        }
+
+        !self.closing
+    }
+}
+
+// handler registered by `ws` routes: performs the handshake and runs the channel in the background
+pub async fn upgrade<C: Channel>(req: HttpRequest, body: web::Payload) -> Result<HttpResponse, actix_web::Error> {
+    let (response, session, stream) = actix_ws::handle(&req, body)?;
+
+    rt::spawn(run::<C>(req, session, stream.aggregate_continuations()));
+
+    Ok(response)
+}
+
+async fn run<C: Channel>(req: HttpRequest, mut session: Session, mut stream: AggregatedMessageStream) {
+    let mut channel = C::default();
+    let mut socket = Socket::default();
+    let mut last_seen = Instant::now();
+
+    channel.on_connect(&mut socket, &req);
+    let mut open = socket.flush(&mut session).await;
+
+    while open {
+        let message = match rt::time::timeout(HEARTBEAT_INTERVAL, stream.recv()).await {
+            Ok(Some(Ok(message))) => message,
+            Ok(_) => break,
+            Err(_) => {
+                if last_seen.elapsed() > CLIENT_TIMEOUT {
+                    break;
+                }
+
+                open = session.ping(b"").await.is_ok();
+                continue;
+            }
+        };
+
+        last_seen = Instant::now();
+
+        match message {
+            AggregatedMessage::Text(text) => {
+                match serde_json::from_str::<C::Message>(&text) {
+                    Ok(message) => channel.on_message(&mut socket, message),
+                    Err(e) => socket.send(&json!({ "status": "error", "message": format!("Invalid message: {}", e) }))
+                }
+                open = socket.flush(&mut session).await;
+            },
+            AggregatedMessage::Ping(bytes) => open = session.pong(&bytes).await.is_ok(),
+            AggregatedMessage::Close(_) => break,
+            AggregatedMessage::Pong(_) | AggregatedMessage::Binary(_) => {}
+        }
+    }
+
+    channel.on_close();
+    let _ = session.close(None).await;
+}
@@ -0,0 +1,230 @@
+use {
+    actix_web::{
+        dev::{ forward_ready, Service, ServiceRequest, ServiceResponse, Transform },
+        error::InternalError,
+        http::{ header::*, Method },
+        Error, HttpMessage
+    },
+    std::{
+        cell::Cell,
+        future::{ ready, Future, Ready },
+        pin::Pin,
+        rc::Rc
+    }
+};
+
+// middleware of `cors(origins = ["https://example.com"], methods = [GET, POST], credentials)` clauses;
+// when scopes with a policy are nested, the innermost one answers
+#[derive(Debug, Clone)]
+pub struct Cors {
+    origins: &'static [&'static str],
+    methods: Option<&'static [&'static str]>,
+    headers: Option<&'static [&'static str]>,
+    expose: &'static [&'static str],
+    credentials: bool,
+    max_age: Option<u32>
+}
+
+impl Cors {
+    // "*" allows any origin
+    pub fn new(origins: &'static [&'static str]) -> Self {
+        Cors { origins, methods: None, headers: None, expose: &[], credentials: false, max_age: None }
+    }
+
+    // without it, preflights allow whatever the route answers, going by its `Allow` header
+    pub fn methods(mut self, methods: &'static [&'static str]) -> Self {
+        self.methods = Some(methods);
+        self
+    }
+
+    // without it, preflights allow the headers they ask for
+    pub fn headers(mut self, headers: &'static [&'static str]) -> Self {
+        self.headers = Some(headers);
+        self
+    }
+
+    pub fn expose(mut self, headers: &'static [&'static str]) -> Self {
+        self.expose = headers;
+        self
+    }
+
+    pub fn credentials(mut self) -> Self {
+        self.credentials = true;
+        self
+    }
+
+    pub fn max_age(mut self, seconds: u32) -> Self {
+        self.max_age = Some(seconds);
+        self
+    }
+
+    fn any_origin(&self) -> bool {
+        self.origins.contains(&"*")
+    }
+
+    fn allows(&self, origin: &str) -> bool {
+        self.any_origin() || self.origins.iter().any(|allowed| allowed.eq_ignore_ascii_case(origin))
+    }
+
+    fn apply(&self, request: &CorsRequest, headers: &mut HeaderMap, success: bool) {
+        let origin = match request.origin.to_str() {
+            Ok(origin) if self.allows(origin) => request.origin.clone(),
+            _ => return
+        };
+
+        match &request.preflight {
+            Some((method, requested_headers)) => {
+                // preflights of paths without routes are left to fail
+                if !success {
+                    return
+                }
+
+                let methods = match self.methods {
+                    Some(methods) => methods.join(", "),
+                    None => headers.get(ALLOW).and_then(|allow| allow.to_str().ok()).unwrap_or_default().to_string()
+                };
+
+                if !methods.split(',').any(|allowed| allowed.trim().eq_ignore_ascii_case(method)) {
+                    return
+                }
+
+                insert(headers, ACCESS_CONTROL_ALLOW_METHODS, &methods);
+
+                match (self.headers, requested_headers) {
+                    (Some(allowed), _) => insert(headers, ACCESS_CONTROL_ALLOW_HEADERS, &allowed.join(", ")),
+                    (None, Some(requested)) => { headers.insert(ACCESS_CONTROL_ALLOW_HEADERS, requested.clone()); },
+                    (None, None) => {}
+                }
+
+                if let Some(max_age) = self.max_age {
+                    headers.insert(ACCESS_CONTROL_MAX_AGE, HeaderValue::from(max_age));
+                }
+            },
+            None => if !self.expose.is_empty() {
+                insert(headers, ACCESS_CONTROL_EXPOSE_HEADERS, &self.expose.join(", "));
+            }
+        }
+
+        // browsers refuse credentials alongside a wildcard, so the origin is echoed instead
+        if self.any_origin() && !self.credentials {
+            headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, HeaderValue::from_static("*"));
+        } else {
+            headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+            headers.append(VARY, HeaderValue::from_static("Origin"));
+        }
+
+        if self.credentials {
+            headers.insert(ACCESS_CONTROL_ALLOW_CREDENTIALS, HeaderValue::from_static("true"));
+        }
+    }
+}
+
+fn insert(headers: &mut HeaderMap, name: HeaderName, value: &str) {
+    if let Ok(value) = HeaderValue::from_str(value) {
+        headers.insert(name, value);
+    }
+}
+
+struct CorsRequest {
+    origin: HeaderValue,
+    // the method and headers a preflight asks for
+    preflight: Option<(String, Option<HeaderValue>)>
+}
+
+impl CorsRequest {
+    fn new(req: &ServiceRequest) -> Option<Self> {
+        let origin = req.headers().get(ORIGIN)?.clone();
+
+        let preflight = match req.headers().get(ACCESS_CONTROL_REQUEST_METHOD) {
+            Some(method) if req.method() == Method::OPTIONS => Some((
+                method.to_str().unwrap_or_default().to_string(),
+                req.headers().get(ACCESS_CONTROL_REQUEST_HEADERS).cloned()
+            )),
+            _ => None
+        };
+
+        Some( CorsRequest { origin, preflight } )
+    }
+}
+
+// the policy of the innermost scope reached so far, applied by whichever middleware finishes first
+#[derive(Clone, Default)]
+struct Innermost(Rc<Cell<Option<Rc<Cors>>>>);
+
+impl<S, B> Transform<S, ServiceRequest> for Cors
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = CorsMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok( CorsMiddleware { service: Rc::new(service), cors: Rc::new(self.clone()) } ))
+    }
+}
+
+pub struct CorsMiddleware<S> {
+    service: Rc<S>,
+    cors: Rc<Cors>
+}
+
+impl<S, B> Service<ServiceRequest> for CorsMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+
+        let request = match CorsRequest::new(&req) {
+            Some(request) => request,
+            None => return Box::pin(service.call(req))
+        };
+
+        let innermost = {
+            let mut extensions = req.extensions_mut();
+            match extensions.get::<Innermost>() {
+                Some(innermost) => innermost.clone(),
+                None => {
+                    let innermost = Innermost::default();
+                    extensions.insert(innermost.clone());
+                    innermost
+                }
+            }
+        };
+        innermost.0.set(Some( Rc::clone(&self.cors) ));
+
+        Box::pin(async move {
+            let result = service.call(req).await;
+
+            let cors = match innermost.0.take() {
+                Some(cors) => cors,
+                None => return result
+            };
+
+            match result {
+                Ok(mut response) => {
+                    let success = response.status().is_success();
+                    cors.apply(&request, response.headers_mut(), success);
+                    Ok(response)
+                },
+                // e.g. timeouts, which browsers would otherwise report as CORS failures
+                Err(error) => {
+                    let mut response = error.error_response();
+                    cors.apply(&request, response.headers_mut(), false);
+                    Err( InternalError::from_response(error.to_string(), response).into() )
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,39 @@
+// extractor configs installed at the root of every route table; a `JsonConfig` or `PathConfig`
+// given to `data(..)` without an error handler of its own gets the JSON errors of these as well
+
+use {
+    actix_web::{
+        error::{ JsonPayloadError, PathError },
+        http::StatusCode,
+        web, Error, HttpRequest
+    },
+    crate::error::{
+        AppError,
+        helpers::{ not_found, bad_request, unprocessable_entity }
+    }
+};
+
+pub fn path_config() -> web::PathConfig {
+    web::PathConfig::default().error_handler(path_error)
+}
+
+pub fn json_config() -> web::JsonConfig {
+    web::JsonConfig::default().error_handler(json_error)
+}
+
+// path extraction errors as JSON `AppError`s instead of actix's plain text 404
+pub fn path_error(_err: PathError, _req: &HttpRequest) -> Error {
+    not_found("Invalid path parameter").into()
+}
+
+// JSON body errors as `AppError`s, keeping actix's status codes apart from the catch all 400
+pub fn json_error(err: JsonPayloadError, _req: &HttpRequest) -> Error {
+    match err {
+        JsonPayloadError::OverflowKnownLength { .. } | JsonPayloadError::Overflow { .. } =>
+            AppError::new("Request body is too large", Some(StatusCode::PAYLOAD_TOO_LARGE)).into(),
+        JsonPayloadError::ContentType =>
+            AppError::new("Expected an application/json body", Some(StatusCode::UNSUPPORTED_MEDIA_TYPE)).into(),
+        JsonPayloadError::Deserialize(_) => unprocessable_entity("Invalid JSON body").into(),
+        _ => bad_request("Invalid request body").into()
+    }
+}
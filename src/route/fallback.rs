@@ -0,0 +1,69 @@
+use {
+    actix_web::{
+        dev::ResourceDef,
+        http::{ header, Method },
+        web, HttpRequest, HttpResponse, Route
+    },
+    std::sync::Arc,
+    crate::error::helpers::{ not_found, method_not_allowed }
+};
+
+// default service of the app and of every generated scope: a 405 listing the allowed methods
+// when the path matches routes declared with other methods, a 404 otherwise; `prefix` is where
+// the route table is mounted
+pub fn scope(prefix: &str, routes: &'static [(&'static str, &'static str)]) -> Route {
+    let answer = answer(prefix, routes);
+
+    web::to(move |req: HttpRequest| {
+        let response = answer(&req);
+        async move { response }
+    })
+}
+
+// the responses of `scope`, for default services answering some requests themselves
+pub(crate) fn answer(prefix: &str, routes: &'static [(&'static str, &'static str)]) -> impl Fn(&HttpRequest) -> HttpResponse + Clone {
+    let routes: Arc<Vec<(&'static str, ResourceDef)>> = Arc::new(
+        routes.iter().map(|(method, path)| (*method, ResourceDef::new(format!("{}{}", prefix, path)))).collect()
+    );
+
+    move |req: &HttpRequest| {
+        let mut allowed: Vec<&'static str> = Vec::new();
+
+        for (method, def) in routes.iter() {
+            if def.is_match(req.path()) && !allowed.contains(method) {
+                allowed.push(method);
+            }
+        }
+
+        if !allowed.is_empty() && !allowed.contains(&"OPTIONS") {
+            allowed.push("OPTIONS");
+        }
+
+        // the method is declared but a guard rejected the request
+        if allowed.is_empty() || allowed.contains(&req.method().as_str()) {
+            not_found_response()
+        } else {
+            let mut response = crate::view::error(&method_not_allowed("Method not allowed"));
+            if let Ok(value) = header::HeaderValue::from_str(&allowed.join(", ")) {
+                response.headers_mut().insert(header::ALLOW, value);
+            }
+            response
+        }
+    }
+}
+
+// answers OPTIONS on every declared path, unless the route table declares its own
+pub fn options(allow: &'static str) -> Route {
+    web::method(Method::OPTIONS).to(move || async move {
+        HttpResponse::NoContent().insert_header((header::ALLOW, allow)).finish()
+    })
+}
+
+// for paths outside every route, e.g. missing files
+pub fn root() -> Route {
+    web::to(|| async { not_found_response() })
+}
+
+fn not_found_response() -> HttpResponse {
+    crate::view::error(&not_found("No route matches this path"))
+}
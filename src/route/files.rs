@@ -0,0 +1,105 @@
+use {
+    actix_files::{ Files, NamedFile },
+    actix_web::{
+        body::BoxBody,
+        dev::{ HttpServiceFactory, ServiceRequest, ServiceResponse },
+        guard::{ self, Guard },
+        http::{ header, Method, StatusCode },
+        middleware::{ from_fn, Next },
+        web, Error, HttpRequest, Route
+    },
+    std::path::Path,
+    crate::error::helpers::not_found
+};
+
+// directory serving for `files("/assets", "./public")` entries, cached for `max_age` seconds;
+// other methods and missing files get the JSON responses of the surrounding routes
+pub fn files(path: &str, dir: &str, max_age: u32) -> impl HttpServiceFactory {
+    web::scope(path).
+        guard(readable()).
+        wrap(from_fn(move |req: ServiceRequest, next: Next<BoxBody>| cache(max_age, req, next))).
+        service(
+            Files::new("", dir).
+                use_etag(true).
+                use_last_modified(true).
+                default_handler(crate::route::fallback::root())
+        )
+}
+
+// `spa("/", "./dist/index.html")`: serves the index's directory, falling back to the index
+// itself so client side routes work on reload
+pub fn spa(path: &str, index: &'static str, api_prefixes: &'static [&'static str], prefix: &str, routes: &'static [(&'static str, &'static str)]) -> impl HttpServiceFactory {
+    let dir = Path::new(index).parent().unwrap_or_else(|| Path::new("."));
+    let index_file = Path::new(index).file_name().and_then(|f| f.to_str()).unwrap_or("index.html");
+
+    // an empty scope matches every path, which is fine as the app is registered last; Files
+    // itself must be mounted at the full path for nested assets to resolve. Other methods than
+    // GET and HEAD skip it for the fallback
+    web::scope("").
+        guard(readable()).
+        wrap(from_fn(|req: ServiceRequest, next: Next<BoxBody>| cache(3600, req, next))).
+        service(
+            Files::new(path, dir).
+                index_file(index_file).
+                use_etag(true).
+                use_last_modified(true).
+                default_handler(spa_fallback(path, index, api_prefixes, prefix, routes))
+        )
+}
+
+// also installed as the app's default service; requests under an API prefix or with other
+// methods get the JSON 404 or 405 of the route table instead of the index
+pub fn spa_fallback(path: &str, index: &'static str, api_prefixes: &'static [&'static str], prefix: &str, routes: &'static [(&'static str, &'static str)]) -> Route {
+    let mount = path.trim_end_matches('/').to_string();
+    let answer = crate::route::fallback::answer(prefix, routes);
+
+    web::to(move |req: HttpRequest| {
+        let serve_index = is_under(req.path(), &mount) &&
+            !api_prefixes.iter().any(|prefix| is_under(req.path(), prefix)) &&
+            (req.method() == Method::GET || req.method() == Method::HEAD);
+        let answer = if serve_index { None } else { Some(answer(&req)) };
+
+        async move {
+            if let Some(response) = answer {
+                return response
+            }
+
+            match NamedFile::open_async(index).await {
+                Ok(file) => {
+                    let mut response = file.into_response(&req);
+                    response.headers_mut().insert(header::CACHE_CONTROL, header::HeaderValue::from_static("no-cache"));
+                    response
+                },
+                Err(_) => crate::view::error(&not_found("No route matches this path"))
+            }
+        }
+    })
+}
+
+fn readable() -> impl Guard {
+    guard::Any(guard::Get()).or(guard::Head())
+}
+
+// only files are cached, not errors or the index served by the fallback, which sets its own
+async fn cache(max_age: u32, req: ServiceRequest, next: Next<BoxBody>) -> Result<ServiceResponse<BoxBody>, Error> {
+    let mut response = next.call(req).await?;
+    let cacheable = response.status().is_success() || response.status() == StatusCode::NOT_MODIFIED;
+
+    if cacheable && !response.headers().contains_key(header::CACHE_CONTROL) {
+        if let Ok(value) = header::HeaderValue::from_str(&format!("public, max-age={}", max_age)) {
+            response.headers_mut().insert(header::CACHE_CONTROL, value);
+        }
+    }
+
+    Ok(response)
+}
+
+// prefix match on whole segments, so "/api" covers "/api/posts" but not "/apiary"
+fn is_under(path: &str, prefix: &str) -> bool {
+    let prefix = prefix.trim_end_matches('/');
+
+    match path.strip_prefix(prefix) {
+        Some(rest) => rest.is_empty() || rest.starts_with('/'),
+        None => false
+    }
+}
@@ -0,0 +1,19 @@
+use actix_web::{
+    guard::{ Guard, GuardContext, fn_guard },
+    http::header
+};
+
+// matches when the request carries the header, whatever its value
+pub fn header_present(name: &'static str) -> impl Guard {
+    fn_guard(move |ctx: &GuardContext| ctx.head().headers().contains_key(name))
+}
+
+// matches the media type of the request body, ignoring parameters such as charset
+pub fn content_type(mime: &'static str) -> impl Guard {
+    fn_guard(move |ctx: &GuardContext| {
+        match ctx.head().headers().get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok()) {
+            Some(value) => value.split(';').next().unwrap_or("").trim().eq_ignore_ascii_case(mime),
+            None => false
+        }
+    })
+}
@@ -1,9 +1,87 @@
+use {
+    serde::Serialize,
+    serde_json::json,
+    std::borrow::Cow
+};
+
+pub mod channel;
+pub mod cors;
+pub mod extractors;
+pub mod fallback;
+pub mod files;
+pub mod guards;
+pub mod openapi;
+pub mod rate_limit;
+pub mod redirect;
+pub mod timeout;
+pub mod versioning;
+
 pub use build_routes::build_routes;
 
+// routes!{ .. } or routes!(admin_routes [in crate::admin::controllers] => { .. }), the
+// controllers being imported from `crate::controllers` unless another module is given
 #[macro_export]
 macro_rules! routes {
     ( $( $input:tt )* ) => {
-        use crate::controllers::*;
         roolz::route::build_routes!{ $( $input )* }
     }
-}
\ No newline at end of file
+}
+
+// one entry of the ROUTES table generated by `routes!`; paths of mounted tables carry the mount prefix
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RouteInfo {
+    pub method: &'static str,
+    pub path: Cow<'static, str>,
+    pub action: &'static str,
+    pub line: u32
+}
+
+// the `route_info()` of a table mounted under `prefix`
+pub fn prefixed(prefix: &str, routes: Vec<RouteInfo>) -> Vec<RouteInfo> {
+    routes.into_iter().map(|route| {
+        RouteInfo {
+            path: Cow::Owned(format!("{}{}", prefix.trim_end_matches('/'), route.path)),
+            ..route
+        }
+    }).collect()
+}
+
+pub fn table(routes: &[RouteInfo]) -> String {
+    let header = RouteInfo { method: "METHOD", path: Cow::Borrowed("PATH"), action: "ACTION", line: 0 };
+
+    let method_width = column_width(routes, &header, |r| r.method);
+    let path_width = column_width(routes, &header, |r| &r.path);
+    let action_width = column_width(routes, &header, |r| r.action);
+
+    let mut lines: Vec<String> = vec![
+        format!("{:mw$}  {:pw$}  {:aw$}  LINE", header.method, header.path, header.action,
+            mw = method_width, pw = path_width, aw = action_width)
+    ];
+
+    for route in routes {
+        lines.push(
+            format!("{:mw$}  {:pw$}  {:aw$}  {}", route.method, route.path, route.action, route.line,
+                mw = method_width, pw = path_width, aw = action_width)
+        );
+    }
+
+    lines.join("\n")
+}
+
+pub fn json(routes: &[RouteInfo]) -> serde_json::Value {
+    json!(routes)
+}
+
+// prints the route table, or JSON when invoked with `--json`, e.g. from a `src/bin/routes.rs`:
+// fn main() { roolz::route::print_routes(&app::routes::route_info()) }
+pub fn print_routes(routes: &[RouteInfo]) {
+    if std::env::args().any(|arg| arg == "--json") {
+        println!("{}", json(routes));
+    } else {
+        println!("{}", table(routes));
+    }
+}
+
+fn column_width(routes: &[RouteInfo], header: &RouteInfo, column: fn(&RouteInfo) -> &str) -> usize {
+    routes.iter().chain(std::iter::once(header)).map(|r| column(r).len()).max().unwrap_or(0)
+}
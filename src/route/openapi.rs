@@ -0,0 +1,387 @@
+// OpenAPI documents of route tables declaring openapi(..). Parameters and request bodies are
+// inferred from each handler's extractors, but responses cannot be, as handlers return
+// `impl Responder`: routes document theirs with `api(response = Post, status = 201)`, and
+// those without it are listed with a 200 of unspecified content
+
+use {
+    actix_web::{ web, HttpRequest, Handler },
+    serde_json::{ json, Map },
+    crate::route::RouteInfo
+};
+
+pub use serde_json::Value;
+
+// JSON schema of a request or response body
+pub trait Schema {
+    fn schema() -> Value;
+}
+
+macro_rules! primitive_schemas {
+    ( $( $typ:ty => $kind:expr, $format:expr; )* ) => {
+        $(
+            impl Schema for $typ {
+                fn schema() -> Value {
+                    typed($kind, $format)
+                }
+            }
+        )*
+    }
+}
+
+primitive_schemas! {
+    i8 => "integer", Some("int32");
+    i16 => "integer", Some("int32");
+    i32 => "integer", Some("int32");
+    i64 => "integer", Some("int64");
+    u8 => "integer", Some("int32");
+    u16 => "integer", Some("int32");
+    u32 => "integer", Some("int64");
+    u64 => "integer", Some("int64");
+    f32 => "number", Some("float");
+    f64 => "number", Some("double");
+    bool => "boolean", None;
+    String => "string", None;
+}
+
+impl Schema for Value {
+    fn schema() -> Value {
+        json!({})
+    }
+}
+
+impl<T: Schema> Schema for Option<T> {
+    fn schema() -> Value {
+        nullable(T::schema())
+    }
+}
+
+impl<T: Schema> Schema for Vec<T> {
+    fn schema() -> Value {
+        array(T::schema())
+    }
+}
+
+// schema builders, also used by the schemas `table_model` generates
+pub fn typed(kind: &str, format: Option<&str>) -> Value {
+    match format {
+        Some(format) => json!({ "type": kind, "format": format }),
+        None => json!({ "type": kind })
+    }
+}
+
+pub fn array(items: Value) -> Value {
+    json!({ "type": "array", "items": items })
+}
+
+pub fn nullable(mut schema: Value) -> Value {
+    if let Some(object) = schema.as_object_mut() {
+        object.insert(String::from("nullable"), Value::Bool(true));
+    }
+    schema
+}
+
+pub fn object(properties: Vec<(&str, Value)>, required: &[&str]) -> Value {
+    let properties: Map<String, Value> = properties.into_iter().
+        map(|(name, schema)| (name.to_string(), schema)).
+        collect();
+
+    if required.is_empty() {
+        json!({ "type": "object", "properties": properties })
+    } else {
+        json!({ "type": "object", "properties": properties, "required": required })
+    }
+}
+
+// the envelope rendered by `view::error`
+fn error_schema() -> Value {
+    object(
+        vec![ ("status", typed("string", None)), ("message", typed("string", None)) ],
+        &["status", "message"]
+    )
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Operation {
+    pub summary: Option<String>,
+    pub parameters: Vec<Value>,
+    pub request_body: Option<Value>,
+    pub responses: Vec<(u16, Value)>
+}
+
+impl Operation {
+    pub fn summary(mut self, summary: &str) -> Self {
+        self.summary = Some(summary.to_string());
+        self
+    }
+
+    pub fn request<T: Schema>(mut self) -> Self {
+        self.request_body = Some(json_content(T::schema()));
+        self
+    }
+
+    pub fn response<T: Schema>(mut self, status: u16) -> Self {
+        self.responses.push( (status, T::schema()) );
+        self
+    }
+
+    fn to_json(&self, route: &RouteInfo) -> Value {
+        let mut parameters: Vec<Value> = path_params(&route.path).into_iter().map(|(name, pattern)| {
+            json!({ "name": name, "in": "path", "required": true, "schema": param_schema(pattern.as_deref()) })
+        }).collect();
+        parameters.extend( self.parameters.iter().cloned() );
+
+        let mut responses = Map::new();
+
+        // no `api(response = ..)`, so nothing is claimed about the body
+        if self.responses.is_empty() {
+            responses.insert( String::from("200"), json!({ "description": "Success" }) );
+        }
+
+        for (status, schema) in &self.responses {
+            responses.insert(
+                status.to_string(),
+                json!({ "description": "Success", "content": { "application/json": { "schema": schema } } })
+            );
+        }
+
+        responses.insert(
+            String::from("default"),
+            json!({ "description": "Error", "content": { "application/json": { "schema": error_schema() } } })
+        );
+
+        let mut operation = json!({
+            "operationId": route.action,
+            "tags": [ route.action.split("::").next().unwrap_or(route.action) ],
+            "responses": responses
+        });
+
+        if !parameters.is_empty() {
+            operation["parameters"] = Value::Array(parameters);
+        }
+
+        if let Some(summary) = &self.summary {
+            operation["summary"] = json!(summary);
+        }
+
+        if let Some(body) = &self.request_body {
+            operation["requestBody"] = body.clone();
+        }
+
+        operation
+    }
+}
+
+fn json_content(schema: Value) -> Value {
+    json!({ "required": true, "content": { "application/json": { "schema": schema } } })
+}
+
+// what a handler argument contributes to its operation; custom extractors can
+// opt in with an empty `impl Extractor for MyExtractor {}`
+pub trait Extractor {
+    fn describe(_operation: &mut Operation) {}
+}
+
+impl<T: Schema> Extractor for web::Json<T> {
+    fn describe(operation: &mut Operation) {
+        operation.request_body = Some(json_content(T::schema()));
+    }
+}
+
+impl<T: Schema> Extractor for web::Form<T> {
+    fn describe(operation: &mut Operation) {
+        operation.request_body = Some(
+            json!({ "required": true, "content": { "application/x-www-form-urlencoded": { "schema": T::schema() } } })
+        );
+    }
+}
+
+impl<T: Schema> Extractor for web::Query<T> {
+    fn describe(operation: &mut Operation) {
+        let schema = T::schema();
+        let required: Vec<Value> = schema["required"].as_array().cloned().unwrap_or_default();
+
+        if let Some(properties) = schema["properties"].as_object() {
+            for (name, property) in properties {
+                operation.parameters.push(
+                    json!({
+                        "name": name,
+                        "in": "query",
+                        "required": required.contains(&json!(name)),
+                        "schema": property
+                    })
+                );
+            }
+        }
+    }
+}
+
+// path parameters are taken from the route pattern instead
+impl<T> Extractor for web::Path<T> {}
+impl<T: ?Sized> Extractor for web::Data<T> {}
+impl<T: Clone + 'static> Extractor for web::ReqData<T> {}
+impl Extractor for HttpRequest {}
+impl Extractor for web::Payload {}
+impl Extractor for web::Bytes {}
+impl Extractor for String {}
+impl<T: Extractor> Extractor for Option<T> {
+    fn describe(operation: &mut Operation) {
+        T::describe(operation);
+        if let Some(body) = operation.request_body.as_mut() {
+            body["required"] = Value::Bool(false);
+        }
+    }
+}
+
+pub trait Extractors {
+    fn describe(operation: &mut Operation);
+}
+
+macro_rules! extractor_tuples {
+    ( $( ( $( $arg:ident ),* ) )* ) => {
+        $(
+            impl< $( $arg: Extractor ),* > Extractors for ( $( $arg, )* ) {
+                #[allow(unused_variables)]
+                fn describe(operation: &mut Operation) {
+                    $( $arg::describe(operation); )*
+                }
+            }
+        )*
+    }
+}
+
+extractor_tuples! {
+    ()
+    (A)
+    (A, B)
+    (A, B, C)
+    (A, B, C, D)
+    (A, B, C, D, E)
+    (A, B, C, D, E, F)
+    (A, B, C, D, E, F, G)
+    (A, B, C, D, E, F, G, H)
+    (A, B, C, D, E, F, G, H, I)
+    (A, B, C, D, E, F, G, H, I, J)
+    (A, B, C, D, E, F, G, H, I, J, K)
+    (A, B, C, D, E, F, G, H, I, J, K, L)
+}
+
+// infers the request side of an operation from a handler's extractors
+pub fn describe<F, Args>(_handler: &F) -> Operation
+where
+    F: Handler<Args>,
+    Args: Extractors
+{
+    let mut operation = Operation::default();
+    Args::describe(&mut operation);
+    operation
+}
+
+// the operations of a table mounted at `prefix`, see `route::prefixed`
+pub fn prefixed(prefix: &str, operations: Vec<(RouteInfo, Operation)>) -> Vec<(RouteInfo, Operation)> {
+    let (routes, operations): (Vec<RouteInfo>, Vec<Operation>) = operations.into_iter().unzip();
+    crate::route::prefixed(prefix, routes).into_iter().zip(operations).collect()
+}
+
+pub struct Document {
+    title: String,
+    version: String,
+    paths: Map<String, Value>
+}
+
+impl Document {
+    pub fn new(title: &str, version: &str) -> Self {
+        Document {
+            title: title.to_string(),
+            version: version.to_string(),
+            paths: Map::new()
+        }
+    }
+
+    pub fn operation(mut self, route: &RouteInfo, operation: Operation) -> Self {
+        let path = self.paths.entry(openapi_path(&route.path)).or_insert_with(|| json!({}));
+        path[route.method.to_lowercase()] = operation.to_json(route);
+        self
+    }
+
+    pub fn operations(self, operations: Vec<(RouteInfo, Operation)>) -> Self {
+        operations.into_iter().fold(self, |document, (route, operation)| document.operation(&route, operation))
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "openapi": "3.0.3",
+            "info": { "title": self.title, "version": self.version },
+            "paths": self.paths
+        })
+    }
+}
+
+// actix patterns may carry a regex, "/posts/{id:\d+}", which OpenAPI does not allow
+fn openapi_path(path: &str) -> String {
+    let mut result = String::new();
+    let mut rest = path;
+
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        let (name, _, length) = param_at(&rest[start..]);
+        result.push_str(&format!("{{{}}}", name));
+        rest = &rest[start + length..];
+    }
+
+    result.push_str(rest);
+    result
+}
+
+fn path_params(path: &str) -> Vec<(String, Option<String>)> {
+    let mut params: Vec<(String, Option<String>)> = Vec::new();
+    let mut rest = path;
+
+    while let Some(start) = rest.find('{') {
+        let (name, pattern, length) = param_at(&rest[start..]);
+        params.push( (name, pattern) );
+        rest = &rest[start + length..];
+    }
+
+    params
+}
+
+// typed route parameters, e.g. {id:i32} or {slug:regex([a-z-]+)}
+fn param_schema(pattern: Option<&str>) -> Value {
+    match pattern {
+        Some("i8") | Some("i16") | Some("i32") | Some("u8") | Some("u16") => typed("integer", Some("int32")),
+        Some("i64") | Some("i128") | Some("isize") | Some("u32") | Some("u64") | Some("u128") | Some("usize") => typed("integer", Some("int64")),
+        Some("f32") => typed("number", Some("float")),
+        Some("f64") => typed("number", Some("double")),
+        Some("bool") => typed("boolean", None),
+        Some("uuid") | Some("Uuid") => typed("string", Some("uuid")),
+        Some(pattern) => {
+            let regex = pattern.strip_prefix("regex(").and_then(|r| r.strip_suffix(')')).unwrap_or(pattern);
+            json!({ "type": "string", "pattern": format!("^(?:{})$", regex) })
+        },
+        None => typed("string", None)
+    }
+}
+
+// the name and pattern of the parameter opening `text`, and the length of its `{..}`
+fn param_at(text: &str) -> (String, Option<String>, usize) {
+    let mut depth = 0;
+
+    for (i, c) in text.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    let inner = &text[1..i];
+                    return match inner.find(':') {
+                        Some(colon) => (inner[..colon].trim().to_string(), Some(inner[colon + 1..].trim().to_string()), i + 1),
+                        None => (inner.trim().to_string(), None, i + 1)
+                    }
+                }
+            },
+            _ => {}
+        }
+    }
+
+    (text[1..].to_string(), None, text.len())
+}
@@ -0,0 +1,258 @@
+use {
+    actix_web::{
+        body::EitherBody,
+        dev::{ forward_ready, Service, ServiceRequest, ServiceResponse, Transform },
+        http::header::{ HeaderMap, HeaderName, HeaderValue, RETRY_AFTER },
+        Error
+    },
+    std::{
+        collections::HashMap,
+        future::{ ready, Future, Ready },
+        pin::Pin,
+        rc::Rc,
+        sync::{ Arc, Mutex },
+        time::{ Duration, Instant }
+    },
+    crate::error::helpers::too_many_requests
+};
+
+// idle buckets are only dropped once the store holds this many clients
+const PRUNE_THRESHOLD: usize = 10_000;
+
+// how clients are told apart; `RealIp` trusts `Forwarded`/`X-Forwarded-For`, so it is only
+// meant for apps behind a proxy setting them
+#[derive(Debug, Clone, Copy)]
+pub enum Key {
+    Ip,
+    RealIp,
+    Header(&'static str)
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Limit {
+    pub requests: u32,
+    pub period: Duration
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Decision {
+    pub allowed: bool,
+    pub remaining: u32,
+    // until the client's quota is fully restored
+    pub reset: Duration,
+    // until the next request would be allowed
+    pub retry_after: Duration
+}
+
+// backend of the limiter, shared by every worker; implement it for Redis and the like to
+// limit across instances
+pub trait Store: Send + Sync + 'static {
+    fn hit(&self, key: &str, limit: &Limit) -> Decision;
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant
+}
+
+// token buckets holding `requests` tokens, refilled over `period`
+#[derive(Default)]
+pub struct MemoryStore {
+    buckets: Mutex<HashMap<String, Bucket>>
+}
+
+impl Store for MemoryStore {
+    fn hit(&self, key: &str, limit: &Limit) -> Decision {
+        let capacity = f64::from(limit.requests.max(1));
+        let per_second = capacity / limit.period.as_secs_f64().max(0.001);
+        let now = Instant::now();
+
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+
+        if buckets.len() > PRUNE_THRESHOLD {
+            buckets.retain(|_, bucket| bucket.tokens + now.duration_since(bucket.updated).as_secs_f64() * per_second < capacity);
+        }
+
+        let bucket = buckets.entry(key.to_string()).or_insert(Bucket { tokens: capacity, updated: now });
+        bucket.tokens = (bucket.tokens + now.duration_since(bucket.updated).as_secs_f64() * per_second).min(capacity);
+        bucket.updated = now;
+
+        let allowed = bucket.tokens >= 1.0;
+        if allowed {
+            bucket.tokens -= 1.0;
+        }
+
+        Decision {
+            allowed,
+            remaining: bucket.tokens.floor() as u32,
+            reset: Duration::from_secs_f64((capacity - bucket.tokens) / per_second),
+            retry_after: Duration::from_secs_f64(((1.0 - bucket.tokens) / per_second).max(0.0))
+        }
+    }
+}
+
+// middleware of `rate_limit(10 per minute by ip)` clauses
+#[derive(Clone)]
+pub struct RateLimit {
+    id: &'static str,
+    limit: Limit,
+    key: Key,
+    store: Arc<dyn Store>
+}
+
+impl RateLimit {
+    pub fn new(id: &'static str, requests: u32, period: Duration, key: Key) -> Self {
+        RateLimit {
+            id,
+            limit: Limit { requests, period },
+            key,
+            store: Arc::new(MemoryStore::default())
+        }
+    }
+
+    pub fn store<S: Store>(mut self, store: S) -> Self {
+        self.store = Arc::new(store);
+        self
+    }
+
+    fn client(&self, req: &ServiceRequest) -> String {
+        let ip = || req.peer_addr().map(|addr| addr.ip().to_string()).unwrap_or_default();
+
+        match self.key {
+            Key::Ip => format!("ip:{}", ip()),
+            Key::RealIp => format!("ip:{}", req.connection_info().realip_remote_addr().unwrap_or_default()),
+            // requests without the header share the limit of their address
+            Key::Header(name) => match req.headers().get(name).and_then(|v| v.to_str().ok()) {
+                Some(value) => format!("header:{}", value),
+                None => format!("ip:{}", ip())
+            }
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref LIMITERS: Mutex<HashMap<&'static str, RateLimit>> = Mutex::new(HashMap::new());
+}
+
+// route tables are configured once per worker, so limiters are kept by id to share their buckets
+pub fn shared(id: &'static str, build: impl FnOnce() -> RateLimit) -> RateLimit {
+    let mut limiters = LIMITERS.lock().unwrap_or_else(|e| e.into_inner());
+    limiters.entry(id).or_insert_with(build).clone()
+}
+
+fn insert_headers(headers: &mut HeaderMap, limit: &Limit, decision: &Decision) {
+    let values = [
+        ("ratelimit-limit", limit.requests.to_string()),
+        ("ratelimit-remaining", decision.remaining.to_string()),
+        ("ratelimit-reset", decision.reset.as_secs_f64().ceil().to_string())
+    ];
+
+    for (name, value) in values.iter() {
+        if let Ok(value) = HeaderValue::from_str(value) {
+            headers.insert(HeaderName::from_static(name), value);
+        }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimit
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = RateLimitMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok( RateLimitMiddleware { service: Rc::new(service), limiter: self.clone() } ))
+    }
+}
+
+pub struct RateLimitMiddleware<S> {
+    service: Rc<S>,
+    limiter: RateLimit
+}
+
+impl<S, B> Service<ServiceRequest> for RateLimitMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let limiter = &self.limiter;
+        let key = format!("{}|{}", limiter.id, limiter.client(&req));
+        let limit = limiter.limit;
+        let decision = limiter.store.hit(&key, &limit);
+
+        if !decision.allowed {
+            let mut response = crate::view::error(&too_many_requests("Too many requests"));
+            insert_headers(response.headers_mut(), &limit, &decision);
+            response.headers_mut().insert(RETRY_AFTER, HeaderValue::from(decision.retry_after.as_secs_f64().ceil() as u64));
+
+            return Box::pin(ready(Ok( req.into_response(response).map_into_right_body() )))
+        }
+
+        let service = Rc::clone(&self.service);
+
+        Box::pin(async move {
+            let mut response = service.call(req).await?;
+            insert_headers(response.headers_mut(), &limit, &decision);
+            Ok( response.map_into_left_body() )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limit() -> Limit {
+        Limit { requests: 2, period: Duration::from_secs(60) }
+    }
+
+    #[test]
+    fn denies_once_the_bucket_is_empty() {
+        let store = MemoryStore::default();
+
+        let first = store.hit("client", &limit());
+        assert!(first.allowed);
+        assert_eq!(first.remaining, 1);
+
+        let second = store.hit("client", &limit());
+        assert!(second.allowed);
+        assert_eq!(second.remaining, 0);
+        assert_eq!(second.reset.as_secs_f64().round(), 60.0);
+
+        let third = store.hit("client", &limit());
+        assert!(!third.allowed);
+        assert_eq!(third.retry_after.as_secs_f64().round(), 30.0);
+
+        // other keys have their own bucket
+        assert!(store.hit("other", &limit()).allowed);
+    }
+
+    #[test]
+    fn refills_over_the_period() {
+        let store = MemoryStore::default();
+        store.hit("client", &limit());
+        store.hit("client", &limit());
+
+        // half the period restores one of the two tokens
+        store.buckets.lock().unwrap().get_mut("client").unwrap().updated -= Duration::from_secs(30);
+        let decision = store.hit("client", &limit());
+        assert!(decision.allowed);
+        assert_eq!(decision.remaining, 0);
+
+        // never beyond capacity
+        store.buckets.lock().unwrap().get_mut("client").unwrap().updated -= Duration::from_secs(600);
+        assert_eq!(store.hit("client", &limit()).remaining, 1);
+    }
+}
@@ -0,0 +1,76 @@
+use {
+    actix_web::{
+        guard,
+        http::{ header, StatusCode },
+        web, HttpRequest, HttpResponse, Route
+    }
+};
+
+// handler for `redirect "/old/{id}" => "/new/{id}"` routes: fills the target's placeholders
+// from the matched path and keeps the original query string; other methods than GET and HEAD
+// get the scope's 405
+pub fn to(target: &'static str, status: u16) -> Route {
+    web::route().guard(guard::Any(guard::Get()).or(guard::Head())).to(move |req: HttpRequest| {
+        let location = location(&req, target);
+        let status = StatusCode::from_u16(status).unwrap_or(StatusCode::FOUND);
+
+        async move {
+            HttpResponse::build(status).
+                insert_header((header::LOCATION, location)).
+                finish()
+        }
+    })
+}
+
+fn location(req: &HttpRequest, target: &str) -> String {
+    let mut location = String::new();
+    let mut rest = target;
+
+    while let Some(start) = rest.find('{') {
+        location.push_str(&rest[..start]);
+
+        match rest[start..].find('}') {
+            Some(end) => {
+                let name = &rest[start + 1..start + end];
+                location.push_str(req.match_info().get(name).unwrap_or(""));
+                rest = &rest[start + end + 1..];
+            },
+            None => {
+                rest = &rest[start..];
+                break;
+            }
+        }
+    }
+
+    location.push_str(rest);
+
+    if !req.query_string().is_empty() && !location.contains('?') {
+        location.push('?');
+        location.push_str(req.query_string());
+    }
+
+    location
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        actix_web::test::TestRequest
+    };
+
+    #[test]
+    fn fills_placeholders() {
+        let req = TestRequest::with_uri("/old/5/comments/9").param("id", "5").param("comment", "9").to_http_request();
+        assert_eq!(location(&req, "/posts/{id}#comment-{comment}"), "/posts/5#comment-9");
+        assert_eq!(location(&req, "https://example.com/{id}"), "https://example.com/5");
+    }
+
+    #[test]
+    fn keeps_the_query_string() {
+        let req = TestRequest::with_uri("/old/5?ref=mail").param("id", "5").to_http_request();
+        assert_eq!(location(&req, "/posts/{id}"), "/posts/5?ref=mail");
+        // targets with their own query keep it instead
+        assert_eq!(location(&req, "/posts?id={id}"), "/posts?id=5");
+    }
+}
@@ -0,0 +1,111 @@
+use {
+    actix_web::{
+        dev::{ forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform },
+        error::InternalError, rt, Error, FromRequest, HttpMessage, HttpRequest
+    },
+    std::{
+        future::{ ready, Future, Ready },
+        pin::Pin,
+        rc::Rc,
+        time::{ Duration, Instant }
+    },
+    crate::error::helpers::gateway_timeout
+};
+
+// when the request has to be answered, extracted by handlers to budget their outgoing calls
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Deadline {
+    at: Option<Instant>
+}
+
+impl Deadline {
+    // None for routes without a timeout
+    pub fn remaining(&self) -> Option<Duration> {
+        self.at.map(|at| at.saturating_duration_since(Instant::now()))
+    }
+
+    pub fn expired(&self) -> bool {
+        self.remaining() == Some(Duration::from_secs(0))
+    }
+}
+
+impl FromRequest for Deadline {
+    type Error = Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        ready(Ok( req.extensions().get::<Deadline>().copied().unwrap_or_default() ))
+    }
+}
+
+impl crate::route::openapi::Extractor for Deadline {}
+
+// middleware of `timeout(5s)` clauses: drops the handler future once the limit is reached
+#[derive(Debug, Clone, Copy)]
+pub struct Timeout {
+    limit: Duration
+}
+
+impl Timeout {
+    pub fn new(limit: Duration) -> Self {
+        Timeout { limit }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for Timeout
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = TimeoutMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok( TimeoutMiddleware { service: Rc::new(service), limit: self.limit } ))
+    }
+}
+
+pub struct TimeoutMiddleware<S> {
+    service: Rc<S>,
+    limit: Duration
+}
+
+impl<S, B> Service<ServiceRequest> for TimeoutMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let at = Instant::now() + self.limit;
+
+        // nested timeouts, e.g. on a scope and one of its routes, keep the earliest deadline
+        let deadline = match req.extensions().get::<Deadline>().and_then(|d| d.at) {
+            Some(existing) if existing < at => existing,
+            _ => at
+        };
+        req.extensions_mut().insert(Deadline { at: Some(deadline) });
+
+        let service = Rc::clone(&self.service);
+        let limit = self.limit;
+
+        Box::pin(async move {
+            match rt::time::timeout(limit, service.call(req)).await {
+                Ok(response) => response,
+                // the request went down with the handler, so the response travels as an error
+                Err(_) => {
+                    let response = crate::view::error(&gateway_timeout("Request timed out"));
+                    Err( InternalError::from_response("Request timed out", response).into() )
+                }
+            }
+        })
+    }
+}
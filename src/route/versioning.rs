@@ -0,0 +1,90 @@
+use {
+    actix_web::{
+        guard::{ self, Guard },
+        http::header::{ self, HeaderMap },
+        middleware::DefaultHeaders
+    }
+};
+
+// guard of the routes in a `version(..)` block negotiated by header
+pub fn version(version: u32, default: bool) -> impl Guard {
+    guard::fn_guard(move |ctx| {
+        match requested(ctx.head().headers()) {
+            Some(requested) => requested == version,
+            None => default
+        }
+    })
+}
+
+// `Accept-Version: 2`, or a media type parameter like `Accept: application/json; version=2`
+pub fn requested(headers: &HeaderMap) -> Option<u32> {
+    if let Some(value) = headers.get("Accept-Version").and_then(|v| v.to_str().ok()) {
+        return parse(value)
+    }
+
+    let accept = headers.get(header::ACCEPT)?.to_str().ok()?;
+
+    accept.split(',').find_map(|media_type| {
+        media_type.split(';').skip(1).find_map(|param| {
+            let (key, value) = param.split_once('=')?;
+            if key.trim().eq_ignore_ascii_case("version") {
+                parse(value.trim().trim_matches('"'))
+            } else {
+                None
+            }
+        })
+    })
+}
+
+// headers of deprecated versions: `since` in seconds since the epoch, as RFC 9745 wants it, and
+// the sunset as an HTTP date
+pub fn deprecation(since: i64, sunset: Option<&'static str>) -> DefaultHeaders {
+    let headers = DefaultHeaders::new().add(("Deprecation", format!("@{}", since)));
+
+    match sunset {
+        Some(sunset) => headers.add(("Sunset", sunset)),
+        None => headers
+    }
+}
+
+// "2", "v2" and "2.1" all ask for version 2
+fn parse(value: &str) -> Option<u32> {
+    let value = value.trim();
+    let value = value.strip_prefix(['v', 'V']).unwrap_or(value);
+
+    value.split('.').next()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        actix_web::http::header::{ HeaderName, HeaderValue }
+    };
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(HeaderName::from_static(name), HeaderValue::from_static(value));
+        }
+        headers
+    }
+
+    #[test]
+    fn reads_accept_version() {
+        assert_eq!(requested(&headers(&[("accept-version", "2")])), Some(2));
+        assert_eq!(requested(&headers(&[("accept-version", "v3")])), Some(3));
+        assert_eq!(requested(&headers(&[("accept-version", "2.1")])), Some(2));
+        assert_eq!(requested(&headers(&[("accept-version", "latest")])), None);
+    }
+
+    #[test]
+    fn reads_accept_parameters() {
+        assert_eq!(requested(&headers(&[("accept", "application/json; version=4")])), Some(4));
+        assert_eq!(requested(&headers(&[("accept", "text/html, application/json;charset=utf-8;Version=\"5\"")])), Some(5));
+        assert_eq!(requested(&headers(&[("accept", "application/json")])), None);
+        assert_eq!(requested(&headers(&[])), None);
+        // the dedicated header wins
+        assert_eq!(requested(&headers(&[("accept-version", "1"), ("accept", "application/json; version=4")])), Some(1));
+    }
+}
@@ -0,0 +1,244 @@
+use {
+    actix_web::{
+        body::{ self, MessageBody },
+        dev::{ Service, ServiceFactory, ServiceRequest, ServiceResponse },
+        http::{ header::{ HeaderMap, TryIntoHeaderPair }, Method, StatusCode },
+        test, web, App, Error
+    },
+    serde::{ de::DeserializeOwned, Serialize },
+    serde_json::Value,
+    std::{
+        future::{ Future, IntoFuture },
+        pin::Pin,
+        rc::Rc
+    }
+};
+
+type Call = Rc<dyn Fn(test::TestRequest) -> Pin<Box<dyn Future<Output = Result<ServiceResponse, Error>>>>>;
+
+// the generated routes booted in a test service, e.g.
+// let app = TestApp::new(app::routes::routes).await;
+// app.post("/posts").json(&proxy).await.assert_created();
+#[derive(Clone)]
+pub struct TestApp {
+    call: Call
+}
+
+impl TestApp {
+    pub async fn new(routes: fn(&mut web::ServiceConfig)) -> Self {
+        Self::from_app( App::new().configure(routes) ).await
+    }
+
+    // for apps needing data or middleware besides their routes
+    pub async fn from_app<T, B>(app: App<T>) -> Self
+    where
+        T: ServiceFactory<ServiceRequest, Config = (), Response = ServiceResponse<B>, Error = Error, InitError = ()> + 'static,
+        B: MessageBody + 'static
+    {
+        let service = Rc::new( test::init_service(app).await );
+
+        let call: Call = Rc::new(move |request| {
+            let service = Rc::clone(&service);
+            Box::pin(async move {
+                service.call( request.to_request() ).await.map(ServiceResponse::map_into_boxed_body)
+            })
+        });
+
+        TestApp { call }
+    }
+
+    pub fn request(&self, method: Method, path: impl AsRef<str>) -> TestRequest {
+        TestRequest {
+            call: Rc::clone(&self.call),
+            request: test::TestRequest::default().method(method).uri(path.as_ref())
+        }
+    }
+
+    pub fn get(&self, path: impl AsRef<str>) -> TestRequest {
+        self.request(Method::GET, path)
+    }
+
+    pub fn post(&self, path: impl AsRef<str>) -> TestRequest {
+        self.request(Method::POST, path)
+    }
+
+    pub fn put(&self, path: impl AsRef<str>) -> TestRequest {
+        self.request(Method::PUT, path)
+    }
+
+    pub fn patch(&self, path: impl AsRef<str>) -> TestRequest {
+        self.request(Method::PATCH, path)
+    }
+
+    pub fn delete(&self, path: impl AsRef<str>) -> TestRequest {
+        self.request(Method::DELETE, path)
+    }
+
+    pub fn head(&self, path: impl AsRef<str>) -> TestRequest {
+        self.request(Method::HEAD, path)
+    }
+
+    pub fn options(&self, path: impl AsRef<str>) -> TestRequest {
+        self.request(Method::OPTIONS, path)
+    }
+}
+
+// a request being built, sent when awaited
+pub struct TestRequest {
+    call: Call,
+    request: test::TestRequest
+}
+
+impl TestRequest {
+    pub fn header(mut self, header: impl TryIntoHeaderPair) -> Self {
+        self.request = self.request.insert_header(header);
+        self
+    }
+
+    pub fn json(mut self, data: &impl Serialize) -> Self {
+        self.request = self.request.set_json(data);
+        self
+    }
+
+    pub fn form(mut self, data: &impl Serialize) -> Self {
+        self.request = self.request.set_form(data);
+        self
+    }
+
+    pub fn body(mut self, data: impl Into<web::Bytes>) -> Self {
+        self.request = self.request.set_payload(data);
+        self
+    }
+
+    pub async fn send(self) -> TestResponse {
+        let response = match (self.call)( self.request ).await {
+            Ok(response) => response.into_parts().1,
+            // errors raised by middleware, e.g. timeouts, rendered the way the server would
+            Err(error) => error.error_response()
+        };
+
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = body::to_bytes(response.into_body()).await.unwrap_or_default();
+
+        TestResponse { status, headers, body }
+    }
+}
+
+impl IntoFuture for TestRequest {
+    type Output = TestResponse;
+    type IntoFuture = Pin<Box<dyn Future<Output = TestResponse>>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin( self.send() )
+    }
+}
+
+// a fully read response; assertions panic with the body to show what went wrong and
+// return the response for chaining, e.g. .assert_ok().assert_message("Saved")
+#[derive(Debug)]
+pub struct TestResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: web::Bytes
+}
+
+impl TestResponse {
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|value| value.to_str().ok())
+    }
+
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).to_string()
+    }
+
+    pub fn body(&self) -> Value {
+        serde_json::from_slice(&self.body).unwrap_or_else(|e| panic!("Response body is not JSON ({}): {}", e, self.text()))
+    }
+
+    pub fn json<T: DeserializeOwned>(&self) -> T {
+        serde_json::from_slice(&self.body).unwrap_or_else(|e| panic!("Response body does not match the expected type ({}): {}", e, self.text()))
+    }
+
+    // the `message` of the view::success and view::error envelopes
+    pub fn message(&self) -> Option<String> {
+        self.body().get("message").and_then(Value::as_str).map(String::from)
+    }
+
+    pub fn assert_status(self, status: StatusCode) -> Self {
+        assert_eq!(self.status, status, "Unexpected status, response body: {}", self.text());
+        self
+    }
+
+    pub fn assert_ok(self) -> Self {
+        self.assert_status(StatusCode::OK)
+    }
+
+    pub fn assert_created(self) -> Self {
+        self.assert_status(StatusCode::CREATED)
+    }
+
+    pub fn assert_no_content(self) -> Self {
+        self.assert_status(StatusCode::NO_CONTENT)
+    }
+
+    pub fn assert_bad_request(self) -> Self {
+        self.assert_status(StatusCode::BAD_REQUEST)
+    }
+
+    pub fn assert_unauthorized(self) -> Self {
+        self.assert_status(StatusCode::UNAUTHORIZED)
+    }
+
+    pub fn assert_forbidden(self) -> Self {
+        self.assert_status(StatusCode::FORBIDDEN)
+    }
+
+    pub fn assert_not_found(self) -> Self {
+        self.assert_status(StatusCode::NOT_FOUND)
+    }
+
+    pub fn assert_unprocessable(self) -> Self {
+        self.assert_status(StatusCode::UNPROCESSABLE_ENTITY)
+    }
+
+    // a view::success envelope
+    pub fn assert_success(self) -> Self {
+        assert_eq!(self.body().get("status"), Some(&Value::from("success")), "Expected a success envelope, got: {}", self.text());
+        self
+    }
+
+    // a view::error envelope with the given status
+    pub fn assert_error(self, status: StatusCode) -> Self {
+        let response = self.assert_status(status);
+        assert_eq!(response.body().get("status"), Some(&Value::from("error")), "Expected an error envelope, got: {}", response.text());
+        response
+    }
+
+    // errors may be matched by their reason alone, leaving out the type, kind and origin
+    pub fn assert_message(self, message: &str) -> Self {
+        let actual = self.message();
+        let reason = actual.as_deref().and_then(|actual| actual.rsplit(" - reason: ").next());
+        assert!(actual.as_deref() == Some(message) || reason == Some(message), "Expected message `{}`, response body: {}", message, self.text());
+        self
+    }
+
+    pub fn assert_json(self, expected: &impl Serialize) -> Self {
+        let expected = serde_json::to_value(expected).expect("Expected body could not be serialized");
+        assert_eq!(self.body(), expected, "Unexpected response body");
+        self
+    }
+
+    pub fn assert_header(self, name: &str, value: &str) -> Self {
+        assert_eq!(self.header(name), Some(value), "Unexpected `{}` header", name);
+        self
+    }
+}
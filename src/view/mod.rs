@@ -1,5 +1,6 @@
 use {
-    crate::error::ErrorMeta
+    actix_web::{ HttpRequest, http::header::LINK },
+    crate::{ error::ErrorMeta, model::pagination::{ Page, CursorPage } }
 };
 
 pub use {
@@ -12,6 +13,7 @@ pub use {
 
 // use views module and it's dependencies
 #[macro_export]
+#[allow(clippy::crate_in_macro_def)]
 macro_rules! views {
     ( $( $view:ident )* ) => {
         pub use roolz::view::*;
@@ -62,4 +64,62 @@ pub fn error(e: &AppError) -> HttpResponse {
 			}
 		)
     )
-}
\ No newline at end of file
+}
+
+// a page of records with its `meta` block, linking to the other pages through the
+// `Link` header (RFC 8288) while keeping the rest of the query string
+pub fn paginated<T: Serialize>(page: &Page<T>, req: &HttpRequest) -> HttpResponse {
+    let mut response = json_response(
+        StatusCode::OK,
+        json!(page)
+    );
+
+    let mut links = vec![ (1, "first") ];
+    if page.has_prev() {
+        links.push( (page.page - 1, "prev") );
+    }
+    if page.has_next() {
+        links.push( (page.page + 1, "next") );
+    }
+    links.push( (page.total_pages(), "last") );
+
+    let link = links.iter().map(|(number, rel)| {
+        page_link(req, &format!("page={}&per_page={}", number, page.per_page), rel)
+    }).collect::<Vec<String>>().join(", ");
+
+    if let Ok(link) = link.parse() {
+        response.headers_mut().insert(LINK, link);
+    }
+
+    response
+}
+
+// keyset counterpart of `paginated`, linking to the next page only
+pub fn cursor_paginated<T: Serialize>(page: &CursorPage<T>, req: &HttpRequest) -> HttpResponse {
+    let mut response = json_response(
+        StatusCode::OK,
+        json!(page)
+    );
+
+    // cursors are base64url, safe in a query string as they are
+    if let Some(next) = &page.next {
+        let link = page_link(req, &format!("after={}&per_page={}", next, page.per_page), "next");
+
+        if let Ok(link) = link.parse() {
+            response.headers_mut().insert(LINK, link);
+        }
+    }
+
+    response
+}
+
+// the current path and query with its paging parameters replaced
+fn page_link(req: &HttpRequest, paging: &str, rel: &str) -> String {
+    let mut params: Vec<&str> = req.query_string().split('&').filter(|pair| {
+        let name = pair.split('=').next().unwrap_or_default();
+        !pair.is_empty() && !["page", "per_page", "after"].contains(&name)
+    }).collect();
+
+    params.push(paging);
+    format!("<{}?{}>; rel=\"{}\"", req.path(), params.join("&"), rel)
+}
@@ -0,0 +1,7 @@
+// route tables the macros must reject, with the errors expected in the .stderr next to each case;
+// run with TRYBUILD=overwrite to update them after changing a message
+#[test]
+fn rejects_invalid_route_tables() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
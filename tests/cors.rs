@@ -0,0 +1,128 @@
+use {
+    actix_web::http::StatusCode,
+    roolz::test::{ TestApp, TestRequest }
+};
+
+mod controllers {
+    use {
+        actix_web::Responder,
+        std::time::Duration
+    };
+
+    pub async fn index() -> impl Responder { "index" }
+
+    pub async fn slow() -> impl Responder {
+        actix_web::rt::time::sleep(Duration::from_millis(500)).await;
+        "slow"
+    }
+}
+
+mod routes {
+    roolz::routes! {
+        scope("/api") cors(origins = ["*"], expose = ["ratelimit-remaining"], max_age = 600) {
+            get "/items" @ index
+            post "/items" @ index
+            get "/slow" @ slow timeout(50ms)
+            get "/limited" @ index rate_limit(1 per minute by ip)
+            scope("/staff") cors(origins = ["https://admin.example.com"], methods = [GET, "delete"], credentials) {
+                get "/users" @ index
+                delete "/users" @ index
+            }
+        }
+        scope("/open") {
+            get "/ping" @ index
+        }
+    }
+}
+
+async fn app() -> TestApp {
+    TestApp::new(routes::routes).await
+}
+
+fn preflight(app: &TestApp, path: &str, origin: &str, method: &str) -> TestRequest {
+    app.options(path)
+        .header(("Origin", origin))
+        .header(("Access-Control-Request-Method", method))
+        .header(("Access-Control-Request-Headers", "content-type"))
+}
+
+#[actix_web::test]
+async fn answers_requests_with_an_origin() {
+    let app = app().await;
+
+    app.get("/api/items").header(("Origin", "https://example.com")).await
+        .assert_ok()
+        .assert_header("access-control-allow-origin", "*")
+        .assert_header("access-control-expose-headers", "ratelimit-remaining");
+
+    assert!(app.get("/api/items").await.header("access-control-allow-origin").is_none());
+    // scopes without a policy add nothing
+    assert!(app.get("/open/ping").header(("Origin", "https://example.com")).await.header("access-control-allow-origin").is_none());
+}
+
+#[actix_web::test]
+async fn allows_preflights_of_declared_methods() {
+    let app = app().await;
+
+    preflight(&app, "/api/items", "https://example.com", "POST").await
+        .assert_no_content()
+        .assert_header("access-control-allow-origin", "*")
+        .assert_header("access-control-allow-methods", "GET, HEAD, POST, OPTIONS")
+        .assert_header("access-control-allow-headers", "content-type")
+        .assert_header("access-control-max-age", "600");
+}
+
+#[actix_web::test]
+async fn denies_preflights_of_other_methods_and_paths() {
+    let app = app().await;
+
+    let response = preflight(&app, "/api/items", "https://example.com", "DELETE").await;
+    assert!(response.header("access-control-allow-origin").is_none());
+    assert!(response.header("access-control-allow-methods").is_none());
+
+    let response = preflight(&app, "/api/nothing", "https://example.com", "GET").await.assert_error(StatusCode::NOT_FOUND);
+    assert!(response.header("access-control-allow-origin").is_none());
+}
+
+#[actix_web::test]
+async fn applies_the_innermost_policy() {
+    let app = app().await;
+
+    let response = preflight(&app, "/api/staff/users", "https://example.com", "GET").await;
+    assert!(response.header("access-control-allow-origin").is_none());
+
+    let response = preflight(&app, "/api/staff/users", "https://admin.example.com", "DELETE").await
+        .assert_no_content()
+        .assert_header("access-control-allow-origin", "https://admin.example.com")
+        .assert_header("access-control-allow-methods", "GET, DELETE")
+        .assert_header("access-control-allow-credentials", "true")
+        .assert_header("vary", "Origin");
+    assert!(response.header("access-control-max-age").is_none());
+}
+
+#[actix_web::test]
+async fn echoes_the_origin_of_requests_with_credentials() {
+    let app = app().await;
+
+    let response = app.get("/api/staff/users").header(("Origin", "https://admin.example.com")).await
+        .assert_ok()
+        .assert_header("access-control-allow-origin", "https://admin.example.com")
+        .assert_header("access-control-allow-credentials", "true")
+        .assert_header("vary", "Origin");
+    // the outer policy's exposed headers don't leak into the inner scope
+    assert!(response.header("access-control-expose-headers").is_none());
+}
+
+#[actix_web::test]
+async fn adds_the_headers_to_errors() {
+    let app = app().await;
+
+    app.get("/api/slow").header(("Origin", "https://example.com")).await
+        .assert_error(StatusCode::GATEWAY_TIMEOUT)
+        .assert_header("access-control-allow-origin", "*");
+
+    app.get("/api/limited").header(("Origin", "https://example.com")).await.assert_ok();
+    app.get("/api/limited").header(("Origin", "https://example.com")).await
+        .assert_error(StatusCode::TOO_MANY_REQUESTS)
+        .assert_header("access-control-allow-origin", "*");
+}
@@ -0,0 +1,80 @@
+use {
+    actix_web::http::StatusCode,
+    roolz::test::TestApp
+};
+
+mod controllers {
+    pub async fn ping() -> &'static str { "pong" }
+}
+
+mod routes {
+    roolz::routes! {
+        files("/assets", "tests/public", max_age = 60)
+        spa("/", "tests/public/index.html")
+        scope("/api") {
+            get "/ping" @ ping
+        }
+        scope("/") {
+            get "/health" @ ping
+        }
+        scope("/") guard(host = "api.example.com") {
+            get "/status" @ ping
+        }
+    }
+}
+
+async fn app() -> TestApp {
+    TestApp::new(routes::routes).await
+}
+
+#[actix_web::test]
+async fn caches_files_only() {
+    let app = app().await;
+
+    let asset = app.get("/assets/app.js").await.assert_ok().assert_header("cache-control", "public, max-age=60");
+    assert_eq!(asset.text(), "console.log(1)\n");
+
+    app.get("/assets/missing.js").await.assert_error(StatusCode::NOT_FOUND);
+    assert!(app.get("/assets/missing.js").await.header("cache-control").is_none());
+}
+
+#[actix_web::test]
+async fn serves_the_index_for_client_routes() {
+    let app = app().await;
+
+    let index = app.get("/some/client/route").await.assert_ok().assert_header("cache-control", "no-cache");
+    assert_eq!(index.text(), "<html>app</html>\n");
+    app.get("/app.js").await.assert_ok().assert_header("cache-control", "public, max-age=3600");
+}
+
+#[actix_web::test]
+async fn answers_other_methods_and_api_paths_with_envelopes() {
+    let app = app().await;
+
+    let response = app.post("/nothing").await.assert_error(StatusCode::NOT_FOUND);
+    assert!(response.header("cache-control").is_none());
+    app.post("/assets/app.js").await.assert_error(StatusCode::NOT_FOUND);
+    app.get("/api/nope").await.assert_error(StatusCode::NOT_FOUND);
+    assert_eq!(app.get("/api/ping").await.assert_ok().text(), "pong");
+}
+
+#[actix_web::test]
+async fn serves_root_scopes_next_to_the_app() {
+    let app = app().await;
+
+    assert_eq!(app.get("/health").await.assert_ok().text(), "pong");
+    app.post("/health").await.assert_error(StatusCode::METHOD_NOT_ALLOWED).assert_header("allow", "GET, HEAD, OPTIONS");
+    app.get("/assets/app.js").await.assert_ok();
+    assert_eq!(app.get("/some/client/route").await.assert_ok().text(), "<html>app</html>\n");
+    assert_eq!(app.get("/api/ping").await.assert_ok().text(), "pong");
+}
+
+#[actix_web::test]
+async fn guards_root_scopes_route_by_route() {
+    let app = app().await;
+
+    assert_eq!(app.get("/status").header(("Host", "api.example.com")).await.assert_ok().text(), "pong");
+    // other hosts get the app, not a 404 of the scope
+    assert_eq!(app.get("/status").await.assert_ok().text(), "<html>app</html>\n");
+    assert_eq!(app.get("/health").header(("Host", "api.example.com")).await.assert_ok().text(), "pong");
+}
@@ -0,0 +1,409 @@
+use {
+    actix_web::http::StatusCode,
+    roolz::test::TestApp
+};
+
+mod controllers {
+    use {
+        actix_web::{ web, Responder },
+        std::time::Duration
+    };
+
+    pub async fn index() -> impl Responder { "index" }
+    pub async fn show(id: web::Path<i32>) -> impl Responder { format!("post {}", id) }
+    pub async fn create() -> impl Responder { roolz::view::success("Created") }
+    pub async fn show_dash() -> impl Responder { "dash" }
+
+    // nested resources answer with their matched path
+    pub mod members {
+        use actix_web::HttpRequest;
+
+        pub async fn index(req: HttpRequest) -> String { req.path().to_string() }
+        pub async fn show(req: HttpRequest) -> String { req.path().to_string() }
+    }
+
+    // the middleware each request passed, in order
+    pub async fn trace(req: actix_web::HttpRequest) -> String {
+        req.headers().get_all("x-trace").filter_map(|value| value.to_str().ok()).collect::<Vec<&str>>().join(", ")
+    }
+
+    pub mod middleware {
+        use actix_web::{
+            body::MessageBody,
+            dev::{ ServiceRequest, ServiceResponse },
+            http::header::{ HeaderName, HeaderValue },
+            middleware::Next,
+            Error
+        };
+
+        async fn tag(name: &'static str, mut req: ServiceRequest, next: Next<impl MessageBody>) -> Result<ServiceResponse<impl MessageBody>, Error> {
+            req.headers_mut().append(HeaderName::from_static("x-trace"), HeaderValue::from_static(name));
+            next.call(req).await
+        }
+
+        pub async fn first(req: ServiceRequest, next: Next<impl MessageBody>) -> Result<ServiceResponse<impl MessageBody>, Error> {
+            tag("first", req, next).await
+        }
+
+        pub async fn second(req: ServiceRequest, next: Next<impl MessageBody>) -> Result<ServiceResponse<impl MessageBody>, Error> {
+            tag("second", req, next).await
+        }
+
+        pub async fn inner(req: ServiceRequest, next: Next<impl MessageBody>) -> Result<ServiceResponse<impl MessageBody>, Error> {
+            tag("inner", req, next).await
+        }
+    }
+
+    // counts its messages and echoes them, closing on "bye"
+    pub mod live {
+        use {
+            roolz::route::channel::{ Channel, Socket },
+            serde::Deserialize,
+            std::sync::atomic::{ AtomicUsize, Ordering }
+        };
+
+        pub static CLOSED: AtomicUsize = AtomicUsize::new(0);
+
+        #[derive(Deserialize)]
+        pub struct Message {
+            pub text: String
+        }
+
+        #[derive(Default)]
+        pub struct Echo {
+            count: usize
+        }
+
+        impl Channel for Echo {
+            type Message = Message;
+
+            fn on_connect(&mut self, socket: &mut Socket, req: &actix_web::HttpRequest) {
+                socket.send(&serde_json::json!({ "path": req.path() }));
+            }
+
+            fn on_message(&mut self, socket: &mut Socket, message: Message) {
+                self.count += 1;
+                socket.send(&serde_json::json!({ "echo": message.text, "count": self.count }));
+
+                if message.text == "bye" {
+                    socket.close();
+                }
+            }
+
+            fn on_close(&mut self) {
+                CLOSED.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+    }
+
+    pub async fn note(body: web::Json<serde_json::Value>) -> String { body.to_string() }
+
+    pub fn plain_error(_err: actix_web::error::JsonPayloadError, _req: &actix_web::HttpRequest) -> actix_web::Error {
+        actix_web::error::ErrorBadRequest("plain")
+    }
+
+    pub async fn widget_v1() -> impl Responder { "v1" }
+    pub async fn widget_v2() -> impl Responder { "v2" }
+
+    pub async fn slow() -> impl Responder {
+        actix_web::rt::time::sleep(Duration::from_millis(500)).await;
+        "slow"
+    }
+}
+
+mod routes {
+    roolz::routes! {
+        openapi("/openapi.json", title = "Test")
+        scope("/api") {
+            mount("/admin", admin)
+            get "/posts" @ index as posts
+            post "/posts" @ create api(response = String, status = 201)
+            get "/posts/{id:i32}" @ show as post
+            redirect "/articles/{id}" => "/api/posts/{id}" permanent
+            get "/slow" @ slow timeout(50ms)
+            ws "/live" @ live::Echo
+            get "/limited" @ index rate_limit(100 per day by ip) rate_limit(2 per minute by ip)
+        }
+        scope("/nested") {
+            resources("users") @ members as user only(show) {
+                resources("posts") @ members as post only(index, show)
+            }
+            resources("teams") @ members as team only(show) {
+                resources("posts") @ members as post only(index, show)
+            }
+        }
+        scope("/small") data(actix_web::web::JsonConfig::default().limit(16), actix_web::web::PathConfig::default()) {
+            post "/notes" @ note
+            get "/notes/{id:i32}" @ show
+            scope("/plain") data(actix_web::web::JsonConfig::default().limit(16).error_handler(plain_error)) {
+                post "/notes" @ note
+            }
+        }
+        scope("/shop") {
+            version(1) deprecated(since = "Sun, 01 Jun 2025 00:00:00 GMT", sunset = "Wed, 01 Jan 2031 00:00:00 GMT") {
+                get "/widgets" @ widget_v1
+            }
+            version(2) {
+                get "/widgets" @ widget_v2
+            }
+        }
+        scope("/guarded") guard(host = "api.example.com") {
+            get "/host" @ index
+        }
+        scope("/hooks") {
+            post "/signed" @ create guard(header("X-Signature"))
+            post "/json" @ create guard(content_type = "application/json")
+            get "/version" @ index guard(header("X-Version", "2"))
+        }
+        scope("/wrapped") wrap(actix_web::middleware::from_fn(middleware::first), actix_web::middleware::from_fn(middleware::second)) {
+            get "/trace" @ trace
+            scope("/inner") wrap(actix_web::middleware::from_fn(middleware::inner)) {
+                get "/trace" @ trace
+            }
+        }
+        scope("/") {
+            get "/about" @ index as about
+            get "/trace" @ trace
+            scope("/docs/") {
+                get "/intro" @ index as intro
+            }
+        }
+    }
+
+    roolz::routes!(admin in crate::controllers => {
+        scope("/") {
+            get "/dash" @ show_dash as dash
+        }
+    });
+}
+
+async fn app() -> TestApp {
+    TestApp::new(routes::routes).await
+}
+
+#[actix_web::test]
+async fn serves_routes() {
+    let app = app().await;
+
+    assert_eq!(app.get("/api/posts").await.assert_ok().text(), "index");
+    assert_eq!(app.get(routes::paths::post(5)).await.assert_ok().text(), "post 5");
+    app.post("/api/posts").await.assert_ok().assert_success().assert_message("Created");
+}
+
+#[actix_web::test]
+async fn answers_unknown_paths_and_methods_with_envelopes() {
+    let app = app().await;
+
+    app.get("/api/nothing").await.assert_error(StatusCode::NOT_FOUND);
+    // typed params that do not match are missing paths too
+    app.get("/api/posts/abc").await.assert_error(StatusCode::NOT_FOUND);
+
+    app.delete("/api/posts").await
+        .assert_error(StatusCode::METHOD_NOT_ALLOWED)
+        .assert_header("allow", "GET, HEAD, POST, OPTIONS");
+}
+
+#[actix_web::test]
+async fn answers_head_and_options() {
+    let app = app().await;
+
+    // the server drops the body of HEAD responses, the test service does not
+    assert_eq!(app.head("/api/posts/5").await.assert_ok().text(), "post 5");
+
+    app.options("/api/posts/5").await
+        .assert_no_content()
+        .assert_header("allow", "GET, HEAD, OPTIONS");
+}
+
+#[actix_web::test]
+async fn redirects() {
+    let app = app().await;
+
+    app.get("/api/articles/7?ref=feed").await
+        .assert_status(StatusCode::MOVED_PERMANENTLY)
+        .assert_header("location", "/api/posts/7?ref=feed");
+    app.head("/api/articles/7").await.assert_status(StatusCode::MOVED_PERMANENTLY);
+
+    app.post("/api/articles/7").await
+        .assert_error(StatusCode::METHOD_NOT_ALLOWED)
+        .assert_header("allow", "GET, HEAD, OPTIONS");
+    app.options("/api/articles/7").await.assert_no_content().assert_header("allow", "GET, HEAD, OPTIONS");
+
+    let listed = routes::route_info().into_iter().any(|route| route.method == "GET" && route.path == "/api/articles/{id}");
+    assert!(listed, "redirects are listed with the routes");
+}
+
+#[actix_web::test]
+async fn times_out() {
+    let app = app().await;
+
+    app.get("/api/slow").await
+        .assert_error(StatusCode::GATEWAY_TIMEOUT)
+        .assert_message("Request timed out");
+}
+
+#[actix_web::test]
+async fn stacks_rate_limits() {
+    let app = app().await;
+
+    // each clause keeps its own bucket, so a request spends one token of each
+    app.get("/api/limited").await.assert_ok().assert_header("ratelimit-remaining", "1");
+    app.get("/api/limited").await.assert_ok().assert_header("ratelimit-remaining", "0");
+    app.get("/api/limited").await.assert_error(StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[actix_web::test]
+async fn serves_generated_paths_of_root_scopes() {
+    let app = app().await;
+
+    assert_eq!(routes::paths::about(), "/about");
+    assert_eq!(routes::paths::intro(), "/docs/intro");
+    app.get(routes::paths::about()).await.assert_ok();
+    app.get(routes::paths::intro()).await.assert_ok();
+    app.options(routes::paths::intro()).await.assert_no_content().assert_header("allow", "GET, HEAD, OPTIONS");
+
+    let listed: Vec<String> = routes::route_info().into_iter().map(|route| route.path.to_string()).collect();
+    assert!(listed.contains(&String::from("/about")) && listed.contains(&String::from("/docs/intro")), "{:?}", listed);
+}
+
+#[actix_web::test]
+async fn serves_mounted_tables_where_reported() {
+    let app = app().await;
+
+    let dash = routes::route_info().into_iter().find(|route| route.action == "show_dash").expect("mounted route listed");
+    assert_eq!(dash.path, "/api/admin/dash");
+    assert_eq!(app.get(dash.path.as_ref()).await.assert_ok().text(), "dash");
+
+    let document = routes::openapi();
+    assert!(document["paths"]["/api/admin/dash"]["get"].is_object(), "{}", document["paths"]);
+    assert!(document["paths"]["/api/posts/{id}"]["get"].is_object(), "{}", document["paths"]);
+}
+
+#[actix_web::test]
+async fn documents_declared_responses_only() {
+    let document = routes::openapi();
+    let posts = &document["paths"]["/api/posts"];
+
+    assert_eq!(posts["post"]["responses"]["201"]["content"]["application/json"]["schema"]["type"], "string");
+    // handlers return `impl Responder`, so undeclared responses have no schema
+    assert_eq!(posts["get"]["responses"]["200"], serde_json::json!({ "description": "Success" }));
+}
+
+#[actix_web::test]
+async fn keeps_json_errors_of_scope_extractor_configs() {
+    let app = app().await;
+
+    assert_eq!(app.post("/small/notes").json(&serde_json::json!({})).await.assert_ok().text(), "{}");
+    app.post("/small/notes").json(&serde_json::json!({ "text": "longer than the limit" })).await
+        .assert_error(StatusCode::PAYLOAD_TOO_LARGE)
+        .assert_message("Request body is too large");
+    app.get("/small/notes/99999999999").await
+        .assert_error(StatusCode::NOT_FOUND)
+        .assert_message("Invalid path parameter");
+
+    // configs with their own handler keep it
+    let response = app.post("/small/plain/notes").json(&serde_json::json!({ "text": "longer than the limit" })).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(response.text(), "plain");
+}
+
+#[actix_web::test]
+async fn dates_deprecated_versions() {
+    let app = app().await;
+
+    let response = app.get("/shop/widgets").header(("Accept-Version", "1")).await
+        .assert_ok()
+        .assert_header("deprecation", "@1748736000")
+        .assert_header("sunset", "Wed, 01 Jan 2031 00:00:00 GMT");
+    assert_eq!(response.text(), "v1");
+    app.get("/shop/v1/widgets").await.assert_ok().assert_header("deprecation", "@1748736000");
+
+    let response = app.get("/shop/widgets").await.assert_ok();
+    assert_eq!(response.text(), "v2");
+    assert!(response.header("deprecation").is_none());
+}
+
+#[actix_web::test]
+async fn guards_scopes_and_routes() {
+    let app = app().await;
+
+    app.get("/guarded/host").header(("Host", "api.example.com")).await.assert_ok();
+    app.get("/guarded/host").header(("Host", "example.com")).await.assert_error(StatusCode::NOT_FOUND);
+
+    app.post("/hooks/signed").header(("X-Signature", "abc")).await.assert_ok();
+    app.post("/hooks/signed").await.assert_error(StatusCode::NOT_FOUND);
+
+    app.post("/hooks/json").json(&serde_json::json!({})).await.assert_ok();
+    app.post("/hooks/json").header(("Content-Type", "text/plain")).await.assert_error(StatusCode::NOT_FOUND);
+
+    app.get("/hooks/version").header(("X-Version", "2")).await.assert_ok();
+    app.get("/hooks/version").header(("X-Version", "1")).await.assert_error(StatusCode::NOT_FOUND);
+    // a guarded route still answers other methods with a 405
+    app.delete("/hooks/version").await.assert_error(StatusCode::METHOD_NOT_ALLOWED);
+}
+
+#[actix_web::test]
+async fn runs_channels_over_websockets() {
+    use {
+        awc::ws::{ Frame, Message },
+        futures_util::{ SinkExt, StreamExt },
+        std::sync::atomic::Ordering
+    };
+
+    let mut server = actix_test::start(|| actix_web::App::new().configure(routes::routes));
+    let mut socket = server.ws_at("/api/live").await.unwrap();
+
+    assert_eq!(socket.next().await.unwrap().unwrap(), Frame::Text(r#"{"path":"/api/live"}"#.into()));
+
+    socket.send(Message::Text(r#"{"text":"hi"}"#.into())).await.unwrap();
+    assert_eq!(socket.next().await.unwrap().unwrap(), Frame::Text(r#"{"count":1,"echo":"hi"}"#.into()));
+
+    // invalid messages are answered with an error envelope and leave the connection open
+    socket.send(Message::Text("nope".into())).await.unwrap();
+    match socket.next().await.unwrap().unwrap() {
+        Frame::Text(text) => assert!(std::str::from_utf8(&text).unwrap().contains(r#""status":"error""#)),
+        frame => panic!("Unexpected frame {:?}", frame)
+    }
+
+    socket.send(Message::Ping("beat".into())).await.unwrap();
+    assert_eq!(socket.next().await.unwrap().unwrap(), Frame::Pong("beat".into()));
+
+    socket.send(Message::Text(r#"{"text":"bye"}"#.into())).await.unwrap();
+    assert_eq!(socket.next().await.unwrap().unwrap(), Frame::Text(r#"{"count":2,"echo":"bye"}"#.into()));
+    assert!(matches!(socket.next().await.unwrap().unwrap(), Frame::Close(_)));
+    assert_eq!(controllers::live::CLOSED.load(Ordering::SeqCst), 1);
+
+    // plain requests fail the handshake
+    assert_eq!(server.get("/api/live").send().await.unwrap().status(), StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn runs_middleware_in_declared_order() {
+    let app = app().await;
+
+    assert_eq!(app.get("/wrapped/trace").await.assert_ok().text(), "first, second");
+    assert_eq!(app.get("/wrapped/inner/trace").await.assert_ok().text(), "first, second, inner");
+    // only the scope declaring them runs them
+    assert_eq!(app.get("/trace").await.assert_ok().text(), "");
+}
+
+#[actix_web::test]
+async fn serves_the_document_next_to_root_scopes() {
+    let app = app().await;
+
+    let document = app.get("/openapi.json").await.assert_ok().assert_header("content-type", "application/json").body();
+    assert_eq!(document, routes::openapi());
+    assert_eq!(document["info"]["title"], "Test");
+}
+
+#[actix_web::test]
+async fn prefixes_names_of_nested_resources() {
+    let app = app().await;
+
+    assert_eq!(routes::paths::user(1), "/nested/users/1");
+    assert_eq!(routes::paths::user_post(1, 2), "/nested/users/1/posts/2");
+    assert_eq!(routes::paths::team_posts(3), "/nested/teams/3/posts");
+
+    assert_eq!(app.get(routes::paths::user_post(1, 2)).await.assert_ok().text(), "/nested/users/1/posts/2");
+    assert_eq!(app.get(routes::paths::team_posts(3)).await.assert_ok().text(), "/nested/teams/3/posts");
+}
@@ -0,0 +1,187 @@
+#![cfg(feature = "database")]
+// diesel 1.4 expands table! and its derives into impls the newer non_local_definitions lint flags
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+use roolz::{
+    error::AppResult,
+    model::pagination::{CursorPage, Page},
+    route::openapi::Schema
+};
+
+pub mod schema {
+    table! {
+        posts (id) {
+            id -> Int4,
+            title -> Varchar,
+            body -> Nullable<Text>,
+        }
+    }
+    table! {
+        countries (code) {
+            code -> Varchar,
+            name -> Varchar,
+        }
+    }
+    table! {
+        user_roles (user_id, role_id) {
+            user_id -> Int4,
+            role_id -> Int4,
+            granted_by -> Nullable<Int4>,
+        }
+    }
+    table! {
+        orders (id) {
+            id -> Int8,
+            total -> Int8,
+            page -> Int4,
+            per_page -> Int4,
+            offset -> Int4,
+            after -> Int4,
+            items -> Int4,
+            next -> Int4,
+        }
+    }
+    table! {
+        settings (id) {
+            id -> Int4,
+            value -> Varchar,
+            values -> Nullable<Varchar>,
+            pattern -> Varchar,
+            filter -> Int4,
+            records -> Int4,
+        }
+    }
+}
+
+mod models {
+    pub mod post {
+        use roolz::model::table_model::*;
+
+        #[table_model]
+        #[allow(non_camel_case_types)]
+        pub struct posts {
+            #[filterable]
+            pub id: i32,
+            #[filterable]
+            pub title: String,
+            #[filterable]
+            pub body: Option<String>,
+        }
+    }
+
+    pub mod country {
+        use roolz::model::table_model::*;
+
+        #[table_model(client_key)]
+        #[allow(non_camel_case_types)]
+        #[primary_key(code)]
+        pub struct countries {
+            pub code: String,
+            pub name: String,
+        }
+    }
+
+    pub mod user_role {
+        use roolz::model::table_model::*;
+
+        #[table_model(primary_key(user_id, role_id))]
+        #[allow(non_camel_case_types)]
+        pub struct user_roles {
+            pub user_id: i32,
+            pub role_id: i32,
+            pub granted_by: Option<i32>,
+        }
+    }
+
+    // columns named like the bindings of the generated functions
+    pub mod order {
+        use roolz::model::table_model::*;
+
+        #[table_model(cursor(total, id))]
+        #[allow(non_camel_case_types)]
+        pub struct orders {
+            pub id: i64,
+            #[filterable]
+            pub total: i64,
+            pub page: i32,
+            pub per_page: i32,
+            pub offset: i32,
+            pub after: i32,
+            pub items: i32,
+            pub next: i32,
+        }
+    }
+
+    pub mod setting {
+        use roolz::model::table_model::*;
+
+        #[table_model]
+        #[allow(non_camel_case_types)]
+        pub struct settings {
+            pub id: i32,
+            #[filterable]
+            pub value: String,
+            #[filterable]
+            pub values: Option<String>,
+            #[filterable]
+            pub pattern: String,
+            #[filterable]
+            pub filter: i32,
+            pub records: i32,
+        }
+    }
+}
+
+use models::{country, order, post, setting, user_role};
+
+#[test]
+fn keys_models_by_id() {
+    let _: post::PrimaryKey = 1i32;
+    let _: fn(i32) -> AppResult<post::Model> = post::find;
+    let _: fn(i32) -> AppResult<post::Model> = post::delete;
+    let _: fn(i32) -> bool = post::record_exists;
+    let _: fn(i64, i64) -> AppResult<Page<post::Model>> = post::paginate;
+    let _: fn(&mut post::Proxy) -> AppResult<post::Model> = post::Proxy::create;
+    let _: fn(&mut post::Proxy) -> AppResult<post::Model> = post::Proxy::update;
+}
+
+#[test]
+fn keys_models_by_a_declared_client_key() {
+    let _: country::PrimaryKey = String::from("NZ");
+    let _: fn(String) -> AppResult<country::Model> = country::find;
+    let _: fn(String) -> AppResult<country::Model> = country::delete;
+    assert!(country::Proxy::schema()["properties"].get("code").is_some());
+}
+
+#[test]
+fn keys_models_by_composite_keys() {
+    let _: user_role::PrimaryKey = (1i32, 2i32);
+    let _: fn((i32, i32)) -> AppResult<user_role::Model> = user_role::find;
+    let _: fn((i32, i32)) -> AppResult<user_role::Model> = user_role::delete;
+    let _: fn((i32, i32)) -> bool = user_role::record_exists;
+    let _: fn(i64, i64) -> AppResult<Page<user_role::Model>> = user_role::paginate;
+}
+
+#[test]
+fn paginates_after_cursors() {
+    let _: fn(Option<&str>, i64) -> AppResult<CursorPage<order::Model>> = order::paginate_after;
+    let _: fn(i64, i64) -> AppResult<Page<order::Model>> = order::paginate;
+}
+
+#[test]
+fn filters_declared_fields() {
+    let _: fn(post::Filter) -> AppResult<Vec<post::Model>> = post::where_filter;
+    let _: fn(setting::Filter) -> AppResult<Vec<setting::Model>> = setting::where_filter;
+    let _: fn(order::Filter) -> AppResult<Vec<order::Model>> = order::where_filter;
+
+    let filter = setting::Filter { value_eq: Some("on".into()), values_is_null: Some(true), pattern_like: Some("a%".into()), filter_in: Some(vec![1, 2]), ..Default::default() };
+    assert_eq!(filter.value_eq.as_deref(), Some("on"));
+
+    let schema = post::Filter::schema();
+    assert_eq!(schema["properties"]["id_eq"]["type"], "integer");
+    assert_eq!(schema["properties"]["body_is_null"]["type"], "boolean");
+    assert!(schema["properties"].get("id_like").is_none() && schema["properties"].get("title_is_null").is_none());
+}
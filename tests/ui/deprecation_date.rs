@@ -0,0 +1,15 @@
+mod controllers {
+    pub async fn index() -> &'static str { "index" }
+}
+
+mod routes {
+    roolz::routes! {
+        scope("/api") {
+            version(1, 2) deprecated(1, sunset = "Wed, 01 Jan 2031 00:00:00 GMT") {
+                get "/widgets" @ index
+            }
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+mod controllers {
+    pub async fn index() -> &'static str { "index" }
+}
+
+mod routes {
+    roolz::routes! {
+        scope("/api") {
+            get "/posts/{id}" @ index
+        }
+        scope("/api/posts") {
+            get "/{post_id}" @ index
+        }
+    }
+}
+
+fn main() {}
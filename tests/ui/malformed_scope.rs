@@ -0,0 +1,13 @@
+mod controllers {
+    pub async fn index() -> &'static str { "index" }
+}
+
+mod routes {
+    roolz::routes! {
+        scope("/api") {
+            get posts @ index
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+mod controllers {}
+
+mod routes {
+    roolz::routes! {
+        scope("/api") {
+            redirect "/articles/{id}" => "/api/posts/{slug}"
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+#![deny(deprecated)]
+
+mod controllers {
+    pub async fn index() -> &'static str { "index" }
+}
+
+mod routes {
+    roolz::routes! {
+        scope("/api") {
+            get "/posts/{id}" @ index
+            get "/posts/new" @ index
+        }
+    }
+}
+
+fn main() {}
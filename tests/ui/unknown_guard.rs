@@ -0,0 +1,13 @@
+mod controllers {
+    pub async fn index() -> &'static str { "index" }
+}
+
+mod routes {
+    roolz::routes! {
+        scope("/api") guard(ip = "127.0.0.1") {
+            get "/posts" @ index
+        }
+    }
+}
+
+fn main() {}